@@ -0,0 +1,239 @@
+use std::ops::RangeInclusive;
+use util::{BenchOptions, Format, Part};
+
+pub const USAGE: &str = "Usage: aoc run <days> [--part <1|2>] [--input <path|->] [--parallel]
+       aoc verify <days> [--part <1|2>] [--input <path|->]
+       aoc bench <days> [--part <1|2>] [--input <path|->]
+                 [--warmup <n>] [--iterations <n>] [--format <table|json|csv>]
+       aoc new <day>
+
+  <days>        a single day (`15`), an inclusive range (`3..9`), or `all`
+  --input       read the input from <path>, or stdin for `-` (single day only);
+                otherwise $AOC_INPUT_DIR/2021/dayNN.txt (default inputs/) or
+                dayNN/input.txt is used, and days without input are skipped
+  --parallel    run the days concurrently, then print a table of answers,
+                timings and whether they match the recorded answers
+  --warmup      untimed runs before measuring (default 1)
+  --iterations  timed runs per stage (default 10)
+  --format      output format of the benchmark results (default table)
+
+`new` adds a crate for <day> to the workspace and to this runner.";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run {
+        days: RangeInclusive<u8>,
+        part: Option<Part>,
+        input: Option<String>,
+        parallel: bool,
+    },
+    Verify {
+        days: RangeInclusive<u8>,
+        part: Option<Part>,
+        input: Option<String>,
+    },
+    Bench {
+        days: RangeInclusive<u8>,
+        part: Option<Part>,
+        input: Option<String>,
+        options: BenchOptions,
+        format: Format,
+    },
+    New {
+        day: u8,
+    },
+}
+
+impl Command {
+    /// The days to run, verify or bench.
+    pub fn days(&self) -> Option<&RangeInclusive<u8>> {
+        match self {
+            Command::Run { days, .. }
+            | Command::Verify { days, .. }
+            | Command::Bench { days, .. } => Some(days),
+            Command::New { .. } => None,
+        }
+    }
+}
+
+fn parse_days(s: &str) -> Result<RangeInclusive<u8>, String> {
+    let day = |s: &str| match s.parse::<u8>() {
+        Ok(d @ 1..=25) => Ok(d),
+        _ => Err(format!("invalid day `{s}`, expected 1 to 25")),
+    };
+
+    if s == "all" {
+        Ok(1..=25)
+    } else if let Some((from, to)) = s.split_once("..") {
+        let (from, to) = (day(from)?, day(to)?);
+        if from > to {
+            return Err(format!("empty range of days `{s}`"));
+        }
+        Ok(from..=to)
+    } else {
+        let d = day(s)?;
+        Ok(d..=d)
+    }
+}
+
+fn parse_part(s: &str) -> Result<Part, String> {
+    match s {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        s => Err(format!("invalid part `{s}`, expected 1 or 2")),
+    }
+}
+
+fn parse_count(s: &str) -> Result<usize, String> {
+    s.parse().map_err(|_| format!("invalid number `{s}`"))
+}
+
+pub fn parse(args: &[&str]) -> Result<Command, String> {
+    let (cmd, days, rest) = match args {
+        ["new", day] => {
+            return match parse_days(day)? {
+                days if days.start() == days.end() && (1..=25).contains(days.start()) => {
+                    Ok(Command::New { day: *days.start() })
+                }
+                _ => Err(format!(
+                    "`new` needs a single day from 1 to 25, not `{day}`"
+                )),
+            }
+        }
+        ["new", ..] => return Err("`new` takes exactly one day".into()),
+        [cmd @ ("run" | "verify" | "bench"), days, rest @ ..] => (*cmd, parse_days(days)?, rest),
+        [cmd @ ("run" | "verify" | "bench")] => return Err(format!("missing days for `{cmd}`")),
+        [] => return Err("missing command".into()),
+        [cmd, ..] => return Err(format!("unknown command `{cmd}`")),
+    };
+
+    let mut part = None;
+    let mut input = None;
+    let mut options = BenchOptions::default();
+    let mut format = Format::Table;
+    let mut parallel = false;
+
+    let mut rest = rest.iter();
+    while let Some(&flag) = rest.next() {
+        let mut value = || {
+            rest.next()
+                .copied()
+                .ok_or_else(|| format!("missing value for {flag}"))
+        };
+
+        match (cmd, flag) {
+            (_, "--part" | "-p") => part = Some(parse_part(value()?)?),
+            (_, "--input" | "-i") => input = Some(value()?.to_string()),
+            ("run", "--parallel") => parallel = true,
+            ("bench", "--warmup") => options.warmup = parse_count(value()?)?,
+            ("bench", "--iterations" | "-n") => options.iterations = parse_count(value()?)?,
+            ("bench", "--format" | "-f") => format = value()?.parse()?,
+            _ => return Err(format!("unexpected argument `{flag}`")),
+        }
+    }
+
+    if input.is_some() && days.start() != days.end() {
+        return Err("--input can only be used with a single day".into());
+    }
+
+    Ok(match cmd {
+        "bench" => Command::Bench {
+            days,
+            part,
+            input,
+            options,
+            format,
+        },
+        "verify" => Command::Verify { days, part, input },
+        _ => Command::Run {
+            days,
+            part,
+            input,
+            parallel,
+        },
+    })
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        parse(&["run", "15"]),
+        Ok(Command::Run {
+            days: 15..=15,
+            part: None,
+            input: None,
+            parallel: false
+        })
+    );
+
+    assert_eq!(
+        parse(&["run", "3..9", "--part", "2"]),
+        Ok(Command::Run {
+            days: 3..=9,
+            part: Some(Part::Two),
+            input: None,
+            parallel: false
+        })
+    );
+
+    assert_eq!(
+        parse(&["run", "all", "--parallel"]),
+        Ok(Command::Run {
+            days: 1..=25,
+            part: None,
+            input: None,
+            parallel: true
+        })
+    );
+
+    assert_eq!(
+        parse(&["run", "7", "--input", "-"]),
+        Ok(Command::Run {
+            days: 7..=7,
+            part: None,
+            input: Some("-".into()),
+            parallel: false
+        })
+    );
+
+    assert_eq!(
+        parse(&["bench", "19", "--iterations", "50", "--format", "csv"]),
+        Ok(Command::Bench {
+            days: 19..=19,
+            part: None,
+            input: None,
+            options: BenchOptions {
+                warmup: 1,
+                iterations: 50
+            },
+            format: Format::Csv
+        })
+    );
+
+    assert_eq!(
+        parse(&["verify", "all", "-p", "1"]),
+        Ok(Command::Verify {
+            days: 1..=25,
+            part: Some(Part::One),
+            input: None
+        })
+    );
+
+    assert!(parse(&["run", "x"]).is_err());
+    assert!(parse(&["run", "9..3"]).is_err());
+    assert!(parse(&["run", "30"]).is_err());
+    assert!(parse(&["verify", "0..4"]).is_err());
+    assert_eq!(parse(&["run", "4..4"]).unwrap().days(), Some(&(4..=4)));
+    assert!(parse(&["run", "1", "--part", "3"]).is_err());
+    assert!(parse(&["run", "1", "--part"]).is_err());
+    assert!(parse(&["run", "1..3", "--input", "x.txt"]).is_err());
+    assert!(parse(&["run", "1", "--warmup", "3"]).is_err());
+    assert!(parse(&["verify", "1", "--format", "csv"]).is_err());
+    assert!(parse(&["bench", "1", "--parallel"]).is_err());
+    assert!(parse(&["bench", "1", "--format", "xml"]).is_err());
+    assert_eq!(parse(&["new", "23"]), Ok(Command::New { day: 23 }));
+    assert!(parse(&["new", "26"]).is_err());
+    assert!(parse(&["new", "all"]).is_err());
+    assert!(parse(&["new", "23", "--part", "1"]).is_err());
+    assert!(parse(&["walk"]).is_err());
+}
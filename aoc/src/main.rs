@@ -0,0 +1,226 @@
+mod args;
+mod new;
+mod summary;
+
+use args::{Command, USAGE};
+use rayon::{iter::Either, prelude::*};
+use std::{env, ops::RangeInclusive, process};
+use util::*;
+
+const DAYS: &[Day] = &[
+    Day::of::<day01::Day01>(),
+    Day::of::<day02::Day02>(),
+    Day::of::<day03::Day03>(),
+    Day::of::<day04::Day04>(),
+    Day::of::<day05::Day05>(),
+    Day::of::<day06::Day06>(),
+    Day::of::<day07::Day07>(),
+    Day::of::<day08::Day08>(),
+    Day::of::<day09::Day09>(),
+    Day::of::<day10::Day10>(),
+    Day::of::<day11::Day11>(),
+    Day::of::<day12::Day12>(),
+    Day::of::<day13::Day13>(),
+    Day::of::<day14::Day14>(),
+    Day::of::<day15::Day15>(),
+    Day::of::<day16::Day16>(),
+    Day::of::<day17::Day17>(),
+    Day::of::<day18::Day18>(),
+    Day::of::<day19::Day19>(),
+    Day::of::<day20::Day20>(),
+    Day::of::<day21::Day21>(),
+    Day::of::<day22::Day22>(),
+    Day::of::<day23::Day23>(),
+    Day::of::<day24::Day24>(),
+    Day::of::<day25::Day25>(),
+    // add here
+];
+
+/// The selected days together with their inputs. Missing inputs are skipped when running several
+/// days, but are an error when a single day was asked for.
+fn with_inputs(days: RangeInclusive<u8>, input: Option<&str>) -> Vec<(&'static Day, String)> {
+    let single = days.start() == days.end();
+
+    DAYS.iter()
+        .filter(|d| days.contains(&d.day))
+        .filter_map(|d| match load(d.day, input) {
+            Ok(s) => Some((d, s)),
+            Err(err) if single => {
+                eprintln!("error: {err}");
+                process::exit(1);
+            }
+            Err(err) => {
+                eprintln!("Day {}: skipping, {err}", d.day);
+                None
+            }
+        })
+        .collect()
+}
+
+fn recorded_answers() -> RecordedAnswers {
+    load_answers().unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        process::exit(1);
+    })
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let command = args::parse(&args).unwrap_or_else(|err| {
+        eprintln!("error: {err}\n\n{USAGE}");
+        process::exit(2);
+    });
+
+    if let Some(days) = command.days() {
+        if !DAYS.iter().any(|d| days.contains(&d.day)) {
+            eprintln!("error: no solution for days {days:?}\n\n{USAGE}");
+            process::exit(2);
+        }
+    }
+
+    match command {
+        Command::Run {
+            days,
+            part,
+            input,
+            parallel: false,
+        } => {
+            let mut errors = 0;
+
+            for (d, s) in with_inputs(days, input.as_deref()) {
+                println!("Day {}", d.day);
+                time(|| match (d.run)(&s, part) {
+                    Ok((answers, _)) => println!("{answers}"),
+                    Err(err) => {
+                        errors += 1;
+                        eprintln!("  error: {err}");
+                    }
+                });
+            }
+
+            if errors > 0 {
+                process::exit(1);
+            }
+        }
+        Command::Run {
+            days,
+            part,
+            input,
+            parallel: true,
+        } => {
+            let recorded = recorded_answers();
+            let inputs = with_inputs(days, input.as_deref());
+            let mut rows = vec![];
+            let mut errors = vec![];
+
+            time(|| {
+                (rows, errors) = inputs
+                    .par_iter()
+                    .map(|(d, s)| {
+                        let (answers, timings) =
+                            (d.run)(s, part).map_err(|err| format!("Day {}: {err}", d.day))?;
+                        let verdicts = recorded
+                            .check_all(d.day, &answers)
+                            .into_iter()
+                            .map(|(_, _, verdict)| verdict)
+                            .collect();
+
+                        Ok(summary::Row {
+                            day: d.day,
+                            answers,
+                            timings,
+                            verdicts,
+                        })
+                    })
+                    .partition_map(|row: Result<_, String>| match row {
+                        Ok(row) => Either::Left(row),
+                        Err(err) => Either::Right(err),
+                    });
+
+                print!("{}", summary::render(&rows));
+            });
+
+            for err in &errors {
+                eprintln!("error: {err}");
+            }
+
+            if !errors.is_empty() || rows.iter().any(summary::Row::failed) {
+                process::exit(1);
+            }
+        }
+        Command::Verify { days, part, input } => {
+            let recorded = recorded_answers();
+
+            let mut failures = 0;
+
+            for (d, s) in with_inputs(days, input.as_deref()) {
+                println!("Day {}", d.day);
+
+                let answers = match (d.run)(&s, part) {
+                    Ok((answers, _)) => answers,
+                    Err(err) => {
+                        failures += 1;
+                        println!("  FAILED, {err}");
+                        continue;
+                    }
+                };
+
+                for (part, actual, verdict) in recorded.check_all(d.day, &answers) {
+                    match verdict {
+                        Verdict::Pass => println!("  {part}: {actual} ok"),
+                        Verdict::Unrecorded => println!("  {part}: {actual} (no recorded answer)"),
+                        Verdict::Fail { expected } => {
+                            failures += 1;
+                            println!("  {part}: {actual} FAILED, expected {expected}");
+                        }
+                    }
+                }
+            }
+
+            if failures > 0 {
+                eprintln!(
+                    "{failures} failure(s), checked against {}",
+                    answers_path().display()
+                );
+                process::exit(1);
+            }
+        }
+        Command::Bench {
+            days,
+            part,
+            input,
+            options,
+            format,
+        } => {
+            let mut errors = 0;
+
+            let reports = with_inputs(days, input.as_deref())
+                .into_iter()
+                .filter_map(|(d, s)| {
+                    (d.bench)(&s, part, &options)
+                        .map_err(|err| {
+                            errors += 1;
+                            eprintln!("Day {}: skipping, {err}", d.day);
+                        })
+                        .ok()
+                })
+                .collect::<Vec<_>>();
+
+            print!("{}", render(&reports, format));
+
+            if errors > 0 {
+                process::exit(1);
+            }
+        }
+        Command::New { day } => {
+            new::scaffold(workspace_root(), day).unwrap_or_else(|err| {
+                eprintln!("error: {err}");
+                process::exit(1);
+            });
+
+            println!("Created day{day:02}, put its input in inputs/{YEAR}/day{day:02}.txt");
+        }
+    }
+}
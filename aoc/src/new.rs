@@ -0,0 +1,180 @@
+use std::{fs, path::Path};
+
+const MAIN: &str = "use dayNN::*;
+use util::*;
+
+fn main() {
+    run_day::<DayNN>();
+}
+";
+
+const LIB: &str = "use util::{ParseError, Solution};
+
+pub struct DayNN;
+
+impl Solution for DayNN {
+    const DAY: u8 = N;
+
+    type Input = Vec<String>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        Ok(s.lines().map(|line| line.to_string()).collect())
+    }
+
+    fn part1(lines: &Self::Input) -> usize {
+        lines.len()
+    }
+
+    fn part2(lines: &Self::Input) -> usize {
+        lines.len()
+    }
+}
+
+pub fn solve(s: &str) -> usize {
+    DayNN::solve(s)
+}
+
+pub fn bonus(s: &str) -> usize {
+    DayNN::bonus(s)
+}
+
+#[test]
+fn test_solve() {
+    let s = \"\";
+
+    assert_eq!(solve(s), 0);
+    assert_eq!(bonus(s), 0);
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<DayNN>();
+}
+";
+
+const MANIFEST: &str = "[package]
+name = \"dayNN\"
+version = \"0.1.0\"
+edition = \"2021\"
+
+[dependencies]
+util = { path = \"../util\" }
+";
+
+/// Puts `line` just above the line containing `marker`, with the same indentation.
+fn insert_before(text: &str, marker: &str, line: &str) -> Result<String, String> {
+    let at = text
+        .find(marker)
+        .ok_or_else(|| format!("could not find `{marker}`"))?;
+    let start = text[..at].rfind('\n').map_or(0, |i| i + 1);
+    let indent = &text[start..at];
+
+    Ok(format!(
+        "{}{indent}{line}\n{}",
+        &text[..start],
+        &text[start..]
+    ))
+}
+
+fn update(path: &Path, f: impl FnOnce(&str) -> Result<String, String>) -> Result<(), String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    let text = f(&text).map_err(|err| format!("{}: {err}", path.display()))?;
+    fs::write(path, text).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/// Creates the crate for `day` under `root`, adds it to the workspace and registers it with the
+/// `aoc` runner. Nothing is touched if the day already exists.
+pub fn scaffold(root: &Path, day: u8) -> Result<(), String> {
+    let name = format!("day{day:02}");
+    let dir = root.join(&name);
+    let template = |s: &str| {
+        s.replace("dayNN", &name)
+            .replace("DayNN", &format!("Day{day:02}"))
+            .replace("= N;", &format!("= {day};"))
+    };
+
+    let workspace = root.join("Cargo.toml");
+    let members =
+        fs::read_to_string(&workspace).map_err(|err| format!("{}: {err}", workspace.display()))?;
+
+    if dir.exists() || members.contains(&format!("\"{name}\"")) {
+        return Err(format!("{name} already exists"));
+    }
+
+    fs::create_dir_all(dir.join("src")).map_err(|err| format!("{}: {err}", dir.display()))?;
+
+    for (file, contents) in [
+        ("Cargo.toml", MANIFEST),
+        ("src/main.rs", MAIN),
+        ("src/lib.rs", LIB),
+    ] {
+        let path = dir.join(file);
+        fs::write(&path, template(contents)).map_err(|err| format!("{}: {err}", path.display()))?;
+    }
+
+    update(&workspace, |s| {
+        insert_before(s, "# add here", &format!("\"{name}\","))
+    })?;
+    update(&root.join("aoc/Cargo.toml"), |s| {
+        insert_before(
+            s,
+            "# add here",
+            &format!("{name} = {{ path = \"../{name}\" }}"),
+        )
+    })?;
+    update(&root.join("aoc/src/main.rs"), |s| {
+        insert_before(s, "// add here", &template("Day::of::<dayNN::DayNN>(),"))
+    })
+}
+
+#[test]
+fn test_scaffold() {
+    let root = std::env::temp_dir().join(format!("aoc-new-{}", std::process::id()));
+    fs::create_dir_all(root.join("aoc/src")).unwrap();
+
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\n  \"util\",\n  \"day01\",\n  # add here\n]\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("aoc/Cargo.toml"),
+        "[dependencies]\nday01 = { path = \"../day01\" }\n# add here\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("aoc/src/main.rs"),
+        "const DAYS: &[Day] = &[\n    Day::of::<day01::Day01>(),\n    // add here\n];\n",
+    )
+    .unwrap();
+
+    assert_eq!(scaffold(&root, 2), Ok(()));
+
+    assert_eq!(
+        fs::read_to_string(root.join("Cargo.toml")).unwrap(),
+        "[workspace]\nmembers = [\n  \"util\",\n  \"day01\",\n  \"day02\",\n  # add here\n]\n"
+    );
+    assert!(fs::read_to_string(root.join("aoc/Cargo.toml"))
+        .unwrap()
+        .contains("day02 = { path = \"../day02\" }\n# add here"));
+    assert!(fs::read_to_string(root.join("aoc/src/main.rs"))
+        .unwrap()
+        .contains("    Day::of::<day02::Day02>(),\n    // add here"));
+
+    let lib = fs::read_to_string(root.join("day02/src/lib.rs")).unwrap();
+    assert!(lib.contains("impl Solution for Day02 {\n    const DAY: u8 = 2;"));
+    assert!(lib.contains("util::verify::<Day02>();"));
+
+    // existing days are left alone
+    fs::write(root.join("day02/src/lib.rs"), "mine").unwrap();
+    assert!(scaffold(&root, 2).is_err());
+    assert!(scaffold(&root, 1).is_err());
+    assert_eq!(
+        fs::read_to_string(root.join("day02/src/lib.rs")).unwrap(),
+        "mine"
+    );
+
+    fs::remove_dir_all(root).unwrap();
+}
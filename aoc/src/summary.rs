@@ -0,0 +1,115 @@
+use std::fmt::Write;
+use util::{Answers, Timings, Verdict};
+
+/// One line of the `run --parallel` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub day: u8,
+    pub answers: Answers,
+    pub timings: Timings,
+    pub verdicts: Vec<Verdict>,
+}
+
+impl Row {
+    pub fn failed(&self) -> bool {
+        self.verdicts
+            .iter()
+            .any(|v| matches!(v, Verdict::Fail { .. }))
+    }
+
+    /// `FAIL` if any answer differs from the recorded one, `ok` if all of them match, and `-` if
+    /// there's nothing to compare against.
+    fn status(&self) -> &'static str {
+        if self.failed() {
+            "FAIL"
+        } else if self.verdicts.iter().all(|v| *v == Verdict::Pass) {
+            "ok"
+        } else {
+            "-"
+        }
+    }
+}
+
+pub fn render(rows: &[Row]) -> String {
+    let answer = |a: &Option<String>| a.clone().unwrap_or_else(|| "-".into());
+
+    let width = |f: fn(&Row) -> &Option<String>| {
+        rows.iter()
+            .map(|row| answer(f(row)).len())
+            .max()
+            .unwrap_or(0)
+            .max(6)
+    };
+    let (w1, w2) = (
+        width(|row| &row.answers.part1),
+        width(|row| &row.answers.part2),
+    );
+
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "{:>3}  {:<w1$}  {:<w2$}  {:>10}  {:>10}  status",
+        "day", "part 1", "part 2", "parse", "solve"
+    )
+    .unwrap();
+
+    for row in rows {
+        writeln!(
+            out,
+            "{:>3}  {:<w1$}  {:<w2$}  {:>10.2?}  {:>10.2?}  {}",
+            row.day,
+            answer(&row.answers.part1),
+            answer(&row.answers.part2),
+            row.timings.parse,
+            row.timings.solve,
+            row.status()
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+#[test]
+fn test_render() {
+    use std::time::Duration;
+
+    let row = |day, part2: &str, verdicts| Row {
+        day,
+        answers: Answers {
+            part1: Some("7".into()),
+            part2: Some(part2.into()),
+        },
+        timings: Timings {
+            parse: Duration::from_micros(15),
+            solve: Duration::from_millis(2),
+        },
+        verdicts,
+    };
+
+    let rows = [
+        row(1, "1234567", vec![Verdict::Pass, Verdict::Pass]),
+        row(2, "8", vec![Verdict::Pass, Verdict::Unrecorded]),
+        row(
+            3,
+            "9",
+            vec![
+                Verdict::Fail {
+                    expected: "8".into(),
+                },
+                Verdict::Pass,
+            ],
+        ),
+    ];
+
+    assert_eq!(
+        render(&rows),
+        "day  part 1  part 2        parse       solve  status
+  1  7       1234567     15.00µs      2.00ms  ok
+  2  7       8           15.00µs      2.00ms  -
+  3  7       9           15.00µs      2.00ms  FAIL
+"
+    );
+    assert!(rows[2].failed());
+}
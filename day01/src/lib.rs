@@ -1,19 +1,47 @@
-use itertools::Itertools;
+mod sweep;
+
+pub use sweep::*;
+use util::{parse_num, ParseError, Solution};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<u64>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        s.lines().map(|line| parse_num(s, line)).collect()
+    }
+
+    fn part1(depths: &Self::Input) -> usize {
+        analyze(depths.iter().copied(), 1).increases
+    }
+
+    fn part2(depths: &Self::Input) -> usize {
+        analyze(depths.iter().copied(), 3).increases
+    }
+}
 
 pub fn solve(s: &str) -> usize {
-    s.lines()
-        .map(|line| line.parse::<usize>().unwrap())
-        .tuple_windows()
-        .filter(|(a, b)| a < b)
-        .count()
+    Day01::solve(s)
 }
 
 pub fn bonus(s: &str) -> usize {
-    s.lines()
-        .map(|line| line.parse::<usize>().unwrap())
-        .tuple_windows()
-        .map(|(a, b, c)| a + b + c)
-        .tuple_windows()
-        .filter(|(a, b)| a < b)
-        .count()
+    Day01::bonus(s)
+}
+
+#[test]
+fn test_solve() {
+    let s = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+
+    assert_eq!(solve(s), 7);
+    assert_eq!(bonus(s), 5);
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day01>();
 }
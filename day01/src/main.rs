@@ -2,10 +2,5 @@ use day01::*;
 use util::*;
 
 fn main() {
-    let s = include_str!("../input.txt");
-
-    time(|| {
-        println!("Num larger: {}", solve(s));
-        println!("Num larger v2: {}", bonus(s));
-    });
+    run_day::<Day01>();
 }
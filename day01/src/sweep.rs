@@ -0,0 +1,175 @@
+use itertools::Itertools;
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead},
+};
+use util::parse_num;
+
+/// Sums of every `size` consecutive depths, keeping only the current window in memory.
+pub struct Windows<I> {
+    depths: I,
+    window: VecDeque<u64>,
+    size: usize,
+    sum: u64,
+}
+
+impl<I: Iterator<Item = u64>> Iterator for Windows<I> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            let depth = self.depths.next()?;
+            self.window.push_back(depth);
+            self.sum += depth;
+
+            if self.window.len() > self.size {
+                self.sum -= self.window.pop_front().unwrap();
+            }
+
+            if self.window.len() == self.size {
+                return Some(self.sum);
+            }
+        }
+    }
+}
+
+pub fn windows<I: IntoIterator<Item = u64>>(depths: I, size: usize) -> Windows<I::IntoIter> {
+    assert!(size > 0, "windows have to hold at least one depth");
+
+    Windows {
+        depths: depths.into_iter(),
+        window: VecDeque::with_capacity(size + 1),
+        size,
+        sum: 0,
+    }
+}
+
+/// A window compared to the one before it. `position` is the index of the window's first depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+    pub position: usize,
+    pub from: u64,
+    pub to: u64,
+}
+
+impl Change {
+    pub fn is_increase(&self) -> bool {
+        self.to > self.from
+    }
+}
+
+pub fn changes(depths: impl IntoIterator<Item = u64>, size: usize) -> impl Iterator<Item = Change> {
+    windows(depths, size)
+        .tuple_windows()
+        .enumerate()
+        .map(|(i, (from, to))| Change {
+            position: i + 1,
+            from,
+            to,
+        })
+}
+
+/// Positions of the windows that are deeper than the one before them.
+pub fn increases(
+    depths: impl IntoIterator<Item = u64>,
+    size: usize,
+) -> impl Iterator<Item = usize> {
+    changes(depths, size)
+        .filter(Change::is_increase)
+        .map(|change| change.position)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SweepReport {
+    pub increases: usize,
+    /// Most increases in a row.
+    pub longest_run: usize,
+    /// Largest increase from one window to the next, 0 if there were none.
+    pub largest_jump: u64,
+}
+
+/// Everything at once, in a single pass and constant memory.
+pub fn analyze(depths: impl IntoIterator<Item = u64>, size: usize) -> SweepReport {
+    let mut report = SweepReport::default();
+    let mut run = 0;
+
+    for change in changes(depths, size) {
+        if change.is_increase() {
+            run += 1;
+            report.increases += 1;
+            report.longest_run = report.longest_run.max(run);
+            report.largest_jump = report.largest_jump.max(change.to - change.from);
+        } else {
+            run = 0;
+        }
+    }
+
+    report
+}
+
+/// One depth per line. A line that isn't a number is an `InvalidData` error wrapping a
+/// `ParseError` for that line.
+pub fn read_depths(reader: impl BufRead) -> impl Iterator<Item = io::Result<u64>> {
+    reader.lines().enumerate().map(|(i, line)| {
+        let line = line?;
+        parse_num(&line, &line).map_err(|mut err| {
+            err.line = i + 1;
+            io::Error::new(io::ErrorKind::InvalidData, err)
+        })
+    })
+}
+
+/// [`analyze`] straight from a reader, stopping at the first error.
+pub fn analyze_reader(reader: impl BufRead, size: usize) -> io::Result<SweepReport> {
+    itertools::process_results(read_depths(reader), |depths| analyze(depths, size))
+}
+
+#[test]
+fn test_windows() {
+    let depths = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    assert_eq!(windows(depths, 1).collect::<Vec<_>>(), depths);
+    assert_eq!(
+        windows(depths, 3).collect::<Vec<_>>(),
+        vec![607, 618, 618, 617, 647, 716, 769, 792]
+    );
+    assert_eq!(windows(depths, 11).count(), 0);
+
+    assert_eq!(
+        increases(depths, 1).collect::<Vec<_>>(),
+        vec![1, 2, 3, 5, 6, 7, 9]
+    );
+    assert_eq!(
+        increases(depths, 3).collect::<Vec<_>>(),
+        vec![1, 4, 5, 6, 7]
+    );
+}
+
+#[test]
+fn test_analyze() {
+    let depths = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    assert_eq!(
+        analyze(depths, 1),
+        SweepReport {
+            increases: 7,
+            longest_run: 3,
+            largest_jump: 33
+        }
+    );
+    assert_eq!(analyze(depths, 3).longest_run, 4);
+    assert_eq!(analyze([], 2), SweepReport::default());
+
+    let log = depths.map(|d| d.to_string()).join("\n");
+    assert_eq!(
+        analyze_reader(log.as_bytes(), 3).unwrap(),
+        analyze(depths, 3)
+    );
+
+    let err = analyze_reader("1\n2\nthree\n4".as_bytes(), 1).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "line 3, column 1: expected a number, found `three`"
+    );
+}
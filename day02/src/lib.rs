@@ -1,38 +1,130 @@
-pub fn solve(s: &str) -> usize {
-    let mut depth = 0;
-    let mut pos = 0;
-
-    for line in s.lines() {
-        if line.starts_with("forward") {
-            pos += line[8..].parse::<usize>().unwrap();
-        } else if line.starts_with("up") {
-            depth -= line[3..].parse::<usize>().unwrap();
-        } else if line.starts_with("down") {
-            depth += line[5..].parse::<usize>().unwrap();
-        }
+use std::{iter, str::FromStr};
+use util::{parse_num, split_once, ParseError, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Forward(i64),
+    Up(i64),
+    Down(i64),
+}
+
+impl FromStr for Command {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (dir, x) = split_once(s, s, " ")?;
+
+        let command = match dir {
+            "forward" => Command::Forward,
+            "up" => Command::Up,
+            "down" => Command::Down,
+            _ => return Err(ParseError::at(s, dir, "expected forward, up or down")),
+        };
+
+        Ok(command(parse_num(s, x)?))
     }
+}
+
+/// Depth grows downwards, so going up from the surface makes it negative.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Position {
+    pub horizontal: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
 
-    depth * pos
+/// How the submarine interprets a command.
+pub trait MovementModel {
+    fn apply(&self, position: Position, command: Command) -> Position;
 }
 
-pub fn bonus(s: &str) -> usize {
-    let mut depth = 0;
-    let mut pos = 0;
-    let mut aim = 0;
+/// `up` and `down` change the depth directly.
+pub struct Plain;
 
-    for line in s.lines() {
-        if line.starts_with("forward") {
-            let x = line[8..].parse::<usize>().unwrap();
-            pos += x;
-            depth += x * aim;
-        } else if line.starts_with("up") {
-            aim -= line[3..].parse::<usize>().unwrap();
-        } else if line.starts_with("down") {
-            aim += line[5..].parse::<usize>().unwrap();
+impl MovementModel for Plain {
+    fn apply(&self, mut position: Position, command: Command) -> Position {
+        match command {
+            Command::Forward(x) => position.horizontal += x,
+            Command::Up(x) => position.depth -= x,
+            Command::Down(x) => position.depth += x,
         }
+
+        position
+    }
+}
+
+/// `up` and `down` change the aim, and moving forward dives along it.
+pub struct Aimed;
+
+impl MovementModel for Aimed {
+    fn apply(&self, mut position: Position, command: Command) -> Position {
+        match command {
+            Command::Forward(x) => {
+                position.horizontal += x;
+                position.depth += x * position.aim;
+            }
+            Command::Up(x) => position.aim -= x,
+            Command::Down(x) => position.aim += x,
+        }
+
+        position
+    }
+}
+
+/// Where the submarine is after each command, starting with where it started.
+pub fn track<'a>(
+    model: &'a impl MovementModel,
+    commands: impl IntoIterator<Item = &'a Command> + 'a,
+) -> impl Iterator<Item = Position> + 'a {
+    let mut commands = commands.into_iter();
+
+    iter::successors(Some(Position::default()), move |&position| {
+        Some(model.apply(position, *commands.next()?))
+    })
+}
+
+pub fn destination(model: &impl MovementModel, commands: &[Command]) -> Position {
+    track(model, commands).last().unwrap()
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Command>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        s.lines()
+            .map(|line| line.parse().map_err(|err: ParseError| err.within(s, line)))
+            .collect()
+    }
+
+    fn part1(commands: &Self::Input) -> i64 {
+        let Position {
+            horizontal, depth, ..
+        } = destination(&Plain, commands);
+
+        horizontal * depth
+    }
+
+    fn part2(commands: &Self::Input) -> i64 {
+        let Position {
+            horizontal, depth, ..
+        } = destination(&Aimed, commands);
+
+        horizontal * depth
     }
+}
 
-    depth * pos
+pub fn solve(s: &str) -> i64 {
+    Day02::solve(s)
+}
+
+pub fn bonus(s: &str) -> i64 {
+    Day02::bonus(s)
 }
 
 #[test]
@@ -47,3 +139,32 @@ forward 2";
     assert_eq!(solve(s), 150);
     assert_eq!(bonus(s), 900);
 }
+
+#[test]
+fn test_track() {
+    let commands = Day02::parse("forward 5\nup 3\ndown 1").unwrap();
+    let depths = track(&Plain, &commands)
+        .map(|p| p.depth)
+        .collect::<Vec<_>>();
+
+    assert_eq!(commands[1], Command::Up(3));
+    assert_eq!(depths, vec![0, 0, -3, -2]);
+    assert_eq!(
+        destination(&Aimed, &commands),
+        Position {
+            horizontal: 5,
+            depth: 0,
+            aim: -2
+        }
+    );
+
+    assert_eq!(
+        Day02::parse("forward 5\nback 3").unwrap_err().to_string(),
+        "line 2, column 1: expected forward, up or down, found `back`"
+    );
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day02>();
+}
@@ -1,78 +1,271 @@
-pub fn solve(s: &str) -> usize {
-    let lines = s.lines().collect::<Vec<&str>>();
-    let n = lines.len();
-    let w = lines[0].len();
-    let mut count = vec![0; w];
-
-    for line in lines {
-        for (i, c) in line.chars().enumerate() {
-            count[i] += if c == '1' { 1 } else { 0 };
+use std::{
+    cmp::Ordering,
+    error::Error,
+    fmt::{self, Display},
+};
+use util::{ParseError, Solution};
+
+/// What to do when a column has as many 1s as 0s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TiePolicy {
+    PreferOne,
+    PreferZero,
+    Error,
+}
+
+impl TiePolicy {
+    /// Whether 1 counts as the most common bit in a tied `column`.
+    fn resolve(self, column: usize) -> Result<bool, Tie> {
+        match self {
+            TiePolicy::PreferOne => Ok(true),
+            TiePolicy::PreferZero => Ok(false),
+            TiePolicy::Error => Err(Tie { column }),
         }
     }
+}
 
-    let gamma_rate: usize = count
-        .iter()
-        .enumerate()
-        .map(|(i, &count)| if count > n / 2 { 1 << (w - i - 1) } else { 0 })
-        .sum();
+/// A column had as many 1s as 0s under [`TiePolicy::Error`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tie {
+    /// Counted from the left.
+    pub column: usize,
+}
 
-    let epsilon_rate: usize = count
-        .iter()
-        .enumerate()
-        .map(|(i, &count)| if count < n / 2 { 1 << (w - i - 1) } else { 0 })
-        .sum();
+impl Display for Tie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "column {} has as many 1s as 0s", self.column)
+    }
+}
+
+impl Error for Tie {}
 
-    gamma_rate * epsilon_rate
+/// The product of two `u128`s, which can take up to 256 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Product {
+    /// Least significant first.
+    limbs: [u64; 4],
 }
 
-pub fn bonus(s: &str) -> usize {
-    let to_bit = |c: char| if c == '1' { 1 } else { 0 };
+impl Product {
+    pub fn of(a: u128, b: u128) -> Self {
+        let (a, b) = ([a as u64, (a >> 64) as u64], [b as u64, (b >> 64) as u64]);
+        let mut limbs = [0; 4];
 
-    let bits: Vec<Vec<usize>> = s
-        .lines()
-        .map(|line| line.chars().map(to_bit).collect())
-        .collect();
+        for i in 0..2 {
+            let mut carry = 0;
+            for j in 0..2 {
+                let n = limbs[i + j] as u128 + a[i] as u128 * b[j] as u128 + carry;
+                limbs[i + j] = n as u64;
+                carry = n >> 64;
+            }
+            limbs[i + 2] = carry as u64;
+        }
 
-    let w = bits[0].len();
+        Product { limbs }
+    }
 
-    let select = |mut bits: Vec<Vec<usize>>, oxygen: bool| {
-        let mut at_bit = 0;
+    /// `None` if it doesn't fit.
+    pub fn to_u128(self) -> Option<u128> {
+        (self.limbs[2] == 0 && self.limbs[3] == 0)
+            .then_some(self.limbs[0] as u128 | (self.limbs[1] as u128) << 64)
+    }
+}
 
-        while bits.len() > 1 {
-            let count_1 = bits.iter().filter(|line| line[at_bit] == 1).count();
-            let count_0 = bits.len() - count_1;
+impl Display for Product {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(n) = self.to_u128() {
+            return write!(f, "{n}");
+        }
 
-            let choose = if oxygen {
-                if count_1 as f32 >= bits.len() as f32 / 2.0 {
-                    1
-                } else {
-                    0
-                }
-            } else {
-                if count_0 as f32 <= bits.len() as f32 / 2.0 {
-                    0
-                } else {
-                    1
-                }
-            };
+        // nineteen decimal digits at a time, least significant first
+        const CHUNK: u128 = 10_000_000_000_000_000_000;
+        let mut chunks = vec![];
+        let mut limbs = self.limbs;
+        while limbs != [0; 4] {
+            let mut rem = 0;
+            for limb in limbs.iter_mut().rev() {
+                let n = rem << 64 | *limb as u128;
+                *limb = (n / CHUNK) as u64;
+                rem = n % CHUNK;
+            }
+            chunks.push(rem);
+        }
+
+        write!(f, "{}", chunks.pop().unwrap())?;
+        for chunk in chunks.iter().rev() {
+            write!(f, "{chunk:019}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A diagnostic report, one packed number per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostics {
+    width: usize,
+    /// Sorted, so numbers sharing their leading bits are next to each other.
+    values: Vec<u128>,
+}
+
+impl Diagnostics {
+    pub const MAX_WIDTH: usize = 128;
+
+    pub fn new(width: usize, mut values: Vec<u128>) -> Self {
+        assert!(
+            (1..=Self::MAX_WIDTH).contains(&width),
+            "reports are 1 to {} bits wide",
+            Self::MAX_WIDTH
+        );
+        assert!(!values.is_empty(), "a report needs at least one number");
+
+        values.sort_unstable();
+        Diagnostics { width, values }
+    }
+
+    fn mask(&self, column: usize) -> u128 {
+        1 << (self.width - 1 - column)
+    }
+
+    fn most_common(
+        ones: usize,
+        zeros: usize,
+        column: usize,
+        policy: TiePolicy,
+    ) -> Result<bool, Tie> {
+        match ones.cmp(&zeros) {
+            Ordering::Greater => Ok(true),
+            Ordering::Less => Ok(false),
+            Ordering::Equal => policy.resolve(column),
+        }
+    }
+
+    /// The most common bit of every column.
+    pub fn gamma(&self, policy: TiePolicy) -> Result<u128, Tie> {
+        (0..self.width).try_fold(0, |gamma, column| {
+            let mask = self.mask(column);
+            let ones = self.values.iter().filter(|&v| v & mask != 0).count();
+            let one = Self::most_common(ones, self.values.len() - ones, column, policy)?;
+
+            Ok(gamma | if one { mask } else { 0 })
+        })
+    }
+
+    /// The least common bit of every column.
+    pub fn epsilon(&self, policy: TiePolicy) -> Result<u128, Tie> {
+        let all = u128::MAX >> (Self::MAX_WIDTH - self.width);
+        Ok(!self.gamma(policy)? & all)
+    }
+
+    pub fn power_consumption(&self, policy: TiePolicy) -> Result<Product, Tie> {
+        Ok(Product::of(self.gamma(policy)?, self.epsilon(policy)?))
+    }
+
+    /// Keeps the numbers with the most common bit, column by column, until one is left.
+    pub fn oxygen_rating(&self, policy: TiePolicy) -> Result<u128, Tie> {
+        self.rating(policy, true)
+    }
+
+    /// Keeps the numbers with the least common bit, column by column, until one is left.
+    pub fn co2_rating(&self, policy: TiePolicy) -> Result<u128, Tie> {
+        self.rating(policy, false)
+    }
+
+    pub fn life_support_rating(&self, policy: TiePolicy) -> Result<Product, Tie> {
+        Ok(Product::of(
+            self.oxygen_rating(policy)?,
+            self.co2_rating(policy)?,
+        ))
+    }
+
+    /// The numbers still in the running always share their bits up to `column`, so they're a
+    /// run of the sorted values, with the ones that have a 1 in `column` last. Each column is
+    /// then a binary search instead of a pass over every number.
+    fn rating(&self, policy: TiePolicy, keep_most_common: bool) -> Result<u128, Tie> {
+        let mut values = &self.values[..];
+
+        for column in 0..self.width {
+            if values.len() == 1 {
+                break;
+            }
+
+            let mask = self.mask(column);
+            let (zeros, ones) = values.split_at(values.partition_point(|v| v & mask == 0));
 
-            bits = bits
-                .clone()
-                .into_iter()
-                .filter(|v| v[at_bit] == choose)
-                .collect();
+            // everyone agrees, so there is nothing to choose between
+            if zeros.is_empty() || ones.is_empty() {
+                continue;
+            }
 
-            at_bit += 1;
+            let one = Self::most_common(ones.len(), zeros.len(), column, policy)?;
+            values = if one == keep_most_common { ones } else { zeros };
         }
 
-        bits[0]
-            .iter()
-            .enumerate()
-            .map(|(i, &b)| b * (1 << (w - i - 1)))
-            .sum::<usize>()
-    };
+        Ok(values[0])
+    }
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Input = Diagnostics;
+    type Part1 = Product;
+    type Part2 = Product;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let width = s.lines().next().map_or(0, |line| line.chars().count());
+        if width == 0 {
+            return Err(ParseError::at(s, &s[..0], "expected a diagnostic report"));
+        }
+
+        if width > Diagnostics::MAX_WIDTH {
+            let first = s.lines().next().unwrap();
+            let message = format!("expected at most {} bits", Diagnostics::MAX_WIDTH);
+            return Err(ParseError::at(s, first, message));
+        }
+
+        let values = s
+            .lines()
+            .map(|line| {
+                if line.chars().count() != width {
+                    return Err(ParseError::at(s, line, format!("expected {width} bits")));
+                }
+
+                line.char_indices().try_fold(0, |value, (i, c)| match c {
+                    '0' => Ok(value << 1),
+                    '1' => Ok(value << 1 | 1),
+                    _ => Err(ParseError::at(
+                        s,
+                        &line[i..i + c.len_utf8()],
+                        "expected a bit",
+                    )),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Diagnostics::new(width, values))
+    }
+
+    fn part1(diagnostics: &Self::Input) -> Product {
+        // the puzzle breaks ties in favour of 1, which never fails
+        diagnostics.power_consumption(TiePolicy::PreferOne).unwrap()
+    }
+
+    fn part2(diagnostics: &Self::Input) -> Product {
+        diagnostics
+            .life_support_rating(TiePolicy::PreferOne)
+            .unwrap()
+    }
+}
+
+pub fn solve(s: &str) -> Product {
+    Day03::solve(s)
+}
 
-    select(bits.clone(), true) * select(bits, false)
+pub fn bonus(s: &str) -> Product {
+    Day03::bonus(s)
 }
 
 #[test]
@@ -90,6 +283,78 @@ fn test_solve() {
 00010
 01010";
 
-    assert_eq!(solve(s), 198);
-    assert_eq!(bonus(s), 230);
+    assert_eq!(solve(s).to_u128(), Some(198));
+    assert_eq!(bonus(s).to_u128(), Some(230));
+
+    let diagnostics = Day03::parse(s).unwrap();
+    assert_eq!(diagnostics.gamma(TiePolicy::Error), Ok(0b10110));
+    assert_eq!(diagnostics.epsilon(TiePolicy::Error), Ok(0b01001));
+    assert_eq!(diagnostics.oxygen_rating(TiePolicy::PreferOne), Ok(0b10111));
+    assert_eq!(diagnostics.co2_rating(TiePolicy::PreferOne), Ok(0b01010));
+    assert_eq!(
+        diagnostics.oxygen_rating(TiePolicy::Error),
+        Err(Tie { column: 4 })
+    );
+}
+
+#[test]
+fn test_ties() {
+    let diagnostics = Day03::parse("10\n01\n11").unwrap();
+    assert_eq!(diagnostics.gamma(TiePolicy::Error), Ok(0b11));
+
+    let diagnostics = Day03::parse("10\n01").unwrap();
+    assert_eq!(diagnostics.gamma(TiePolicy::Error), Err(Tie { column: 0 }));
+    assert_eq!(diagnostics.gamma(TiePolicy::PreferOne), Ok(0b11));
+    assert_eq!(diagnostics.gamma(TiePolicy::PreferZero), Ok(0b00));
+    assert_eq!(diagnostics.epsilon(TiePolicy::PreferZero), Ok(0b11));
+    assert_eq!(diagnostics.oxygen_rating(TiePolicy::PreferZero), Ok(0b01));
+    assert_eq!(diagnostics.co2_rating(TiePolicy::PreferZero), Ok(0b10));
+
+    let wide = format!("1{}\n0{}", "0".repeat(127), "1".repeat(127));
+    let diagnostics = Day03::parse(&wide).unwrap();
+    assert_eq!(diagnostics.gamma(TiePolicy::PreferOne), Ok(u128::MAX));
+
+    assert_eq!(
+        Day03::parse("101\n10").unwrap_err().to_string(),
+        "line 2, column 1: expected 3 bits, found `10`"
+    );
+    assert_eq!(
+        Day03::parse("101\n1x1").unwrap_err().to_string(),
+        "line 2, column 2: expected a bit, found `x`"
+    );
+}
+
+#[test]
+fn test_wide() {
+    let s = format!(
+        "{}{}\n{}{}\n{}{}",
+        "1".repeat(50),
+        "0".repeat(50),
+        "1".repeat(33),
+        "0".repeat(67),
+        "0".repeat(50),
+        "1".repeat(50)
+    );
+
+    // both products take more than 128 bits
+    assert_eq!(
+        solve(&s).to_string(),
+        "187072209556577502045863946325947596191419173175296"
+    );
+    assert_eq!(
+        bonus(&s).to_string(),
+        "1427247692705957345757085512991818042883178496"
+    );
+    assert_eq!(solve(&s).to_u128(), None);
+
+    assert_eq!(
+        Product::of(u128::MAX, u128::MAX).to_string(),
+        "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+    );
+    assert_eq!(Product::of(1 << 64, 1 << 63).to_u128(), Some(1 << 127));
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day03>();
 }
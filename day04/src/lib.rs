@@ -1,84 +1,255 @@
-#![feature(drain_filter)]
+use std::{
+    collections::VecDeque,
+    fmt::{self, Display},
+};
+use util::{parse_num, ParseError, Solution};
 
-type BoardState = Vec<Option<usize>>;
+/// Marks are kept as bits of a `u128`, one per cell, so boards can't be larger than 11x11.
+pub const MAX_SIZE: usize = 11;
 
-fn place_num(board: &mut BoardState, num: usize) -> Option<usize> {
-    for opt in board.iter_mut() {
-        match opt {
-            Some(n) if *n == num => {
-                *opt = None;
-            }
-            _ => (),
+fn line(cells: impl Iterator<Item = usize>) -> u128 {
+    cells.fold(0, |mask, cell| mask | 1 << cell)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bingo {
+    pub draws: Vec<usize>,
+    size: usize,
+    /// Row by row.
+    boards: Vec<Vec<usize>>,
+    /// Every set of cells that wins when all of them are marked.
+    lines: Vec<u128>,
+}
+
+impl Bingo {
+    pub fn new(draws: Vec<usize>, size: usize, boards: Vec<Vec<usize>>) -> Self {
+        assert!(
+            (1..=MAX_SIZE).contains(&size),
+            "boards are 1x1 to {MAX_SIZE}x{MAX_SIZE}"
+        );
+        assert!(
+            boards.iter().all(|board| board.len() == size * size),
+            "boards should all be {size}x{size}"
+        );
+
+        let lines = (0..size)
+            .flat_map(|i| {
+                [
+                    line((0..size).map(|j| i * size + j)),
+                    line((0..size).map(|j| j * size + i)),
+                ]
+            })
+            .collect();
+
+        Bingo {
+            draws,
+            size,
+            boards,
+            lines,
         }
     }
 
-    for i in 0..5 {
-        if (0..5).all(|j| board[i * 5 + j].is_none()) || (0..5).all(|j| board[j * 5 + i].is_none())
-        {
-            return Some(num * board.iter().map(|opt| opt.unwrap_or(0)).sum::<usize>());
+    /// Also lets the two diagonals win.
+    pub fn with_diagonals(mut self) -> Self {
+        let size = self.size;
+        self.lines.push(line((0..size).map(|i| i * size + i)));
+        self.lines
+            .push(line((0..size).map(|i| i * size + size - 1 - i)));
+        self
+    }
+
+    /// Plays the draws in order, yielding every board as it wins.
+    pub fn game(&self) -> Game {
+        Game {
+            bingo: self,
+            marks: vec![0; self.boards.len()],
+            won: vec![false; self.boards.len()],
+            next_draw: 0,
+            wins: VecDeque::new(),
         }
     }
 
-    None
+    /// `None` if the draws run out before anyone wins.
+    pub fn first_win(&self) -> Option<Win> {
+        self.game().next()
+    }
+
+    /// `None` if the draws run out before everyone has won.
+    pub fn last_win(&self) -> Option<Win> {
+        let wins = self.game().collect::<Vec<_>>();
+        if wins.len() < self.boards.len() {
+            return None;
+        }
+
+        wins.last().copied()
+    }
 }
 
-fn parse(s: &str) -> (Vec<usize>, Vec<BoardState>) {
-    let pieces = s.split("\n\n").collect::<Vec<_>>();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Win {
+    pub draw: usize,
+    pub board: usize,
+    /// The winning draw times the sum of the board's unmarked numbers.
+    pub score: usize,
+}
 
-    let numbers = pieces[0]
-        .split(",")
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect::<Vec<_>>();
+/// The score of the board a part asks for, if the draws run out before it wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Score(usize),
+    NoWinner,
+}
 
-    let boards: Vec<BoardState> = pieces[1..]
-        .iter()
-        .map(|s| {
-            s.split_whitespace()
-                .map(|s| Some(s.parse::<usize>().unwrap()))
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+impl From<Option<Win>> for Outcome {
+    fn from(win: Option<Win>) -> Self {
+        win.map_or(Outcome::NoWinner, |win| Outcome::Score(win.score))
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Score(score) => write!(f, "{score}"),
+            Outcome::NoWinner => write!(f, "no winner"),
+        }
+    }
+}
 
-    (numbers, boards)
+pub struct Game<'a> {
+    bingo: &'a Bingo,
+    marks: Vec<u128>,
+    won: Vec<bool>,
+    next_draw: usize,
+    /// Boards that won on the same draw, in board order.
+    wins: VecDeque<Win>,
 }
 
-pub fn solve(s: &str) -> usize {
-    let (numbers, mut boards) = parse(s);
+impl Iterator for Game<'_> {
+    type Item = Win;
+
+    fn next(&mut self) -> Option<Win> {
+        loop {
+            if let Some(win) = self.wins.pop_front() {
+                return Some(win);
+            }
+
+            let draw = *self.bingo.draws.get(self.next_draw)?;
+            self.next_draw += 1;
 
-    loop {
-        for &num in &numbers {
-            for board in &mut boards {
-                if let Some(winning_score) = place_num(board, num) {
-                    return winning_score;
+            for (i, board) in self.bingo.boards.iter().enumerate() {
+                if self.won[i] {
+                    continue;
+                }
+
+                let marks = &mut self.marks[i];
+                for (cell, _) in board.iter().enumerate().filter(|&(_, &n)| n == draw) {
+                    *marks |= 1 << cell;
+                }
+
+                if self.bingo.lines.iter().any(|&line| *marks & line == line) {
+                    let unmarked = board
+                        .iter()
+                        .enumerate()
+                        .filter(|&(cell, _)| *marks & 1 << cell == 0)
+                        .map(|(_, n)| n)
+                        .sum::<usize>();
+
+                    self.won[i] = true;
+                    self.wins.push_back(Win {
+                        draw,
+                        board: i,
+                        score: draw * unmarked,
+                    });
                 }
             }
         }
     }
 }
 
-pub fn bonus(s: &str) -> usize {
-    let (numbers, mut boards) = parse(s);
+fn parse(s: &str) -> Result<Bingo, ParseError> {
+    let mut pieces = s.split("\n\n");
 
-    let mut last_winning_score = 0;
+    let draws = pieces
+        .next()
+        .unwrap_or_default()
+        .trim_end()
+        .split(',')
+        .map(|n| parse_num(s, n))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    loop {
-        for &num in &numbers {
-            boards.drain_filter(|board| {
-                if let Some(winning_score) = place_num(board, num) {
-                    last_winning_score = winning_score;
-                    return true;
-                }
+    // extra blank lines, e.g. after the last board, don't make empty boards
+    let pieces = pieces
+        .map(|board| board.trim_matches('\n'))
+        .filter(|board| !board.trim().is_empty())
+        .collect::<Vec<_>>();
 
-                return false;
-            });
+    let Some(first) = pieces.first() else {
+        return Err(ParseError::at(s, &s[s.len()..], "expected a board"));
+    };
+
+    let size = first.trim_end().lines().count();
+    if size > MAX_SIZE {
+        return Err(ParseError::at(
+            s,
+            first,
+            format!("expected a board of at most {MAX_SIZE}x{MAX_SIZE}"),
+        ));
+    }
 
-            if boards.len() == 0 {
-                return last_winning_score;
+    let boards = pieces
+        .iter()
+        .map(|board| {
+            let numbers = board
+                .split_whitespace()
+                .map(|n| parse_num(s, n))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if board.trim_end().lines().count() != size || numbers.len() != size * size {
+                return Err(ParseError::at(
+                    s,
+                    board,
+                    format!("expected a {size}x{size} board"),
+                ));
             }
-        }
+
+            Ok(numbers)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Bingo::new(draws, size, boards))
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = Bingo;
+    type Part1 = Outcome;
+    type Part2 = Outcome;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part1(bingo: &Self::Input) -> Outcome {
+        bingo.first_win().into()
+    }
+
+    /// No board is the last to win if some never do.
+    fn part2(bingo: &Self::Input) -> Outcome {
+        bingo.last_win().into()
     }
 }
 
+pub fn solve(s: &str) -> Outcome {
+    Day04::solve(s)
+}
+
+pub fn bonus(s: &str) -> Outcome {
+    Day04::bonus(s)
+}
+
 #[test]
 fn test_solve() {
     let s = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
@@ -102,6 +273,95 @@ fn test_solve() {
  2  0 12  3  7
 ";
 
-    assert_eq!(solve(s), 4512);
-    assert_eq!(bonus(s), 1924)
+    assert_eq!(solve(s), Outcome::Score(4512));
+    assert_eq!(bonus(s), Outcome::Score(1924));
+
+    let bingo = parse(s).unwrap();
+    assert_eq!(
+        bingo.game().collect::<Vec<_>>(),
+        vec![
+            Win {
+                draw: 24,
+                board: 2,
+                score: 4512
+            },
+            Win {
+                draw: 16,
+                board: 0,
+                score: 2192
+            },
+            Win {
+                draw: 13,
+                board: 1,
+                score: 1924
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_game() {
+    // 1 2 3
+    // 4 5 6
+    // 7 8 9
+    let board = (1..=9).collect::<Vec<_>>();
+
+    let bingo = Bingo::new(vec![1, 5, 9, 2], 3, vec![board.clone()]);
+    assert_eq!(bingo.first_win(), None);
+    assert_eq!(bingo.last_win(), None);
+
+    let bingo = bingo.with_diagonals();
+    assert_eq!(
+        bingo.first_win(),
+        Some(Win {
+            draw: 9,
+            board: 0,
+            score: 9 * (2 + 3 + 4 + 6 + 7 + 8)
+        })
+    );
+
+    let bingo = Bingo::new(vec![3, 5, 7, 2, 1], 3, vec![board.clone(), board]).with_diagonals();
+    let wins = bingo
+        .game()
+        .map(|win| (win.draw, win.board))
+        .collect::<Vec<_>>();
+    assert_eq!(wins, vec![(7, 0), (7, 1)]);
+    assert_eq!(bingo.last_win().map(|win| win.board), Some(1));
+
+    // the draws run out with the second board unfinished
+    let s = "1,2,4\n\n1 2\n3 4\n\n4 5\n6 7";
+    assert_eq!(solve(s), Outcome::Score(2 * (3 + 4)));
+    assert_eq!(bonus(s), Outcome::NoWinner);
+    assert_eq!(bonus(s).to_string(), "no winner");
+    assert_eq!(solve("1,4\n\n1 2\n3 4"), Outcome::NoWinner);
+
+    assert_eq!(
+        parse("1,2\n\n1 2\n3 4\n\n1 2\n3").unwrap_err().to_string(),
+        "line 6, column 1: expected a 2x2 board, found `1 2`"
+    );
+
+    assert_eq!(solve("1,2\n\n1 2\n3 4\n\n\n"), Outcome::Score(2 * (3 + 4)));
+    assert_eq!(
+        parse("1,2\n\n").unwrap_err().to_string(),
+        "line 3, column 1: expected a board, found the end of the input"
+    );
+
+    let big = (0..12)
+        .map(|row| {
+            (0..12)
+                .map(|col| (row * 12 + col).to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_eq!(
+        parse(&format!("1\n\n{big}")).unwrap_err().to_string(),
+        "line 3, column 1: expected a board of at most 11x11, found `0 1 2 3 4 5 6 7 8 9 10 11`"
+    );
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day04>();
 }
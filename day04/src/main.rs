@@ -2,10 +2,5 @@ use day04::*;
 use util::*;
 
 fn main() {
-    let s = include_str!("../input.txt");
-
-    time(|| {
-        println!("Solution: {}", solve(s));
-        println!("Bonus: {}", bonus(s));
-    });
+    run_day::<Day04>();
 }
@@ -1,66 +1,282 @@
 use std::{collections::HashMap, str::FromStr};
+use util::{parse_num, split_once, ParseError, Solution};
 
 #[derive(
     Debug, PartialEq, Eq, Hash, Clone, Copy, derive_more::Add, derive_more::Sub, derive_more::From,
 )]
-struct Pt {
-    x: i32,
-    y: i32,
-}
-
-impl Pt {
-    fn signum(&self) -> Self {
-        Self {
-            x: self.x.signum(),
-            y: self.y.signum(),
-        }
-    }
+pub struct Pt {
+    x: i64,
+    y: i64,
 }
 
 impl FromStr for Pt {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, y) = s.split_once(",").unwrap();
+        let (x, y) = split_once(s, s, ",")?;
         Ok(Self {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
+            x: parse_num(s, x)?,
+            y: parse_num(s, y)?,
         })
     }
 }
 
-fn count(s: &str, include_diagonals: bool) -> usize {
-    let mut count = HashMap::new();
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn cross(a: Pt, b: Pt) -> i128 {
+    a.x as i128 * b.y as i128 - a.y as i128 * b.x as i128
+}
 
-    for line in s.lines() {
-        let (a, b) = line.split_once(" -> ").unwrap();
-        let mut a = a.parse::<Pt>().unwrap();
-        let b = b.parse::<Pt>().unwrap();
-        let delta = (b - a).signum();
+/// The lattice points from `start` to `start + steps * step`. `step` is the smallest one that
+/// hits both ends, pointing right, or down if it's vertical.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    start: Pt,
+    step: Pt,
+    steps: i64,
+}
 
-        if include_diagonals || (delta.x + delta.y).abs() == 1 {
-            count.entry(a).and_modify(|c| *c += 1).or_insert(1);
+impl Segment {
+    pub fn new(a: Pt, b: Pt) -> Self {
+        let d = b - a;
+        let g = gcd(d.x, d.y);
 
-            loop {
-                a = a + delta;
-                count.entry(a).and_modify(|c| *c += 1).or_insert(1);
+        if g == 0 {
+            // a single point, which might as well be horizontal
+            return Segment {
+                start: a,
+                step: (1, 0).into(),
+                steps: 0,
+            };
+        }
 
-                if a == b {
-                    break;
-                }
+        let step = Pt::from((d.x / g, d.y / g));
+        if step.x < 0 || (step.x == 0 && step.y < 0) {
+            Segment {
+                start: b,
+                step: (-step.x, -step.y).into(),
+                steps: g,
+            }
+        } else {
+            Segment {
+                start: a,
+                step,
+                steps: g,
             }
         }
     }
 
-    count.iter().filter(|(_, &c)| c >= 2).count()
+    pub fn is_axis_aligned(&self) -> bool {
+        self.step.x == 0 || self.step.y == 0
+    }
+
+    fn at(&self, t: i64) -> Pt {
+        (
+            self.start.x + t * self.step.x,
+            self.start.y + t * self.step.y,
+        )
+            .into()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Pt> + '_ {
+        (0..=self.steps).map(|t| self.at(t))
+    }
+
+    /// Identifies the infinite line through the segment: its direction, and `step × p`, which is
+    /// the same for every point `p` on it.
+    fn line(&self) -> (Pt, i128) {
+        (self.step, cross(self.step, self.start))
+    }
+
+    /// Where this meets a segment going in another direction, if that's a lattice point of both.
+    fn crossing(&self, other: &Segment) -> Option<Pt> {
+        let denom = cross(self.step, other.step);
+        if denom == 0 {
+            return None;
+        }
+
+        let d = other.start - self.start;
+        let (s, u) = (cross(d, other.step), cross(d, self.step));
+        if s % denom != 0 || u % denom != 0 {
+            return None;
+        }
+
+        let (s, u) = (s / denom, u / denom);
+        ((0..=self.steps as i128).contains(&s) && (0..=other.steps as i128).contains(&u))
+            .then(|| self.at(s as i64))
+    }
+}
+
+/// Collinear segments, each one an interval of steps along the line from `origin`.
+struct Line {
+    origin: Pt,
+    step: Pt,
+    intervals: Vec<(i64, i64)>,
+    /// Where the coverage changes, and what it changes to, in order.
+    runs: Vec<(i64, usize)>,
+}
+
+impl Line {
+    fn offset(&self, p: Pt) -> i64 {
+        if self.step.x != 0 {
+            (p.x - self.origin.x) / self.step.x
+        } else {
+            (p.y - self.origin.y) / self.step.y
+        }
+    }
+
+    fn sweep(&mut self) {
+        let mut events = self
+            .intervals
+            .iter()
+            .flat_map(|&(from, to)| [(from, 1), (to + 1, -1)])
+            .collect::<Vec<_>>();
+        events.sort_unstable();
+
+        let mut coverage = 0;
+        for (t, delta) in events {
+            coverage += delta;
+
+            match self.runs.last_mut() {
+                Some((start, c)) if *start == t => *c = coverage as usize,
+                _ => self.runs.push((t, coverage as usize)),
+            }
+        }
+    }
+
+    fn coverage(&self, p: Pt) -> usize {
+        let t = self.offset(p);
+        match self.runs.partition_point(|&(start, _)| start <= t) {
+            0 => 0,
+            i => self.runs[i - 1].1,
+        }
+    }
+
+    fn count(&self, threshold: usize) -> usize {
+        self.runs
+            .windows(2)
+            .filter(|run| run[0].1 >= threshold)
+            .map(|run| (run[1].0 - run[0].0) as usize)
+            .sum()
+    }
+}
+
+/// Points covered by at least `threshold` segments. Collinear segments are merged by sweeping
+/// over their intervals, and only the points where lines cross are looked at one by one.
+pub fn count_overlaps(segments: &[Segment], threshold: usize) -> usize {
+    assert!(
+        threshold > 0,
+        "every point is covered by at least 0 segments"
+    );
+
+    let mut lines: Vec<Line> = vec![];
+    let mut index = HashMap::new();
+    let mut line_of = vec![];
+
+    for segment in segments {
+        let i = *index.entry(segment.line()).or_insert_with(|| {
+            lines.push(Line {
+                origin: segment.start,
+                step: segment.step,
+                intervals: vec![],
+                runs: vec![],
+            });
+            lines.len() - 1
+        });
+
+        let from = lines[i].offset(segment.start);
+        lines[i].intervals.push((from, from + segment.steps));
+        line_of.push(i);
+    }
+
+    for line in &mut lines {
+        line.sweep();
+    }
+
+    let mut crossings = HashMap::<Pt, Vec<usize>>::new();
+    for (i, a) in segments.iter().enumerate() {
+        for (j, b) in segments.iter().enumerate().skip(i + 1) {
+            if line_of[i] == line_of[j] {
+                continue;
+            }
+
+            if let Some(p) = a.crossing(b) {
+                crossings
+                    .entry(p)
+                    .or_default()
+                    .extend([line_of[i], line_of[j]]);
+            }
+        }
+    }
+
+    let mut count = lines
+        .iter()
+        .map(|line| line.count(threshold))
+        .sum::<usize>();
+
+    // a crossing was counted once for every line that covers it enough on its own, but should
+    // be counted once if all of them together do
+    for (p, mut through) in crossings {
+        through.sort_unstable();
+        through.dedup();
+
+        let coverage = through.iter().map(|&i| lines[i].coverage(p));
+        let counted = coverage.clone().filter(|&c| c >= threshold).count();
+        let total = coverage.sum::<usize>();
+
+        count = count + (total >= threshold) as usize - counted;
+    }
+
+    count
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Input = Vec<Segment>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let pt = |p: &str| p.parse::<Pt>().map_err(|err| err.within(s, p));
+
+        s.lines()
+            .map(|line| {
+                let (a, b) = split_once(s, line, " -> ")?;
+                Ok(Segment::new(pt(a)?, pt(b)?))
+            })
+            .collect()
+    }
+
+    fn part1(segments: &Self::Input) -> usize {
+        let straight = segments
+            .iter()
+            .copied()
+            .filter(Segment::is_axis_aligned)
+            .collect::<Vec<_>>();
+
+        count_overlaps(&straight, 2)
+    }
+
+    fn part2(segments: &Self::Input) -> usize {
+        count_overlaps(segments, 2)
+    }
 }
 
 pub fn solve(s: &str) -> usize {
-    count(s, false)
+    Day05::solve(s)
 }
 
 pub fn bonus(s: &str) -> usize {
-    count(s, true)
+    Day05::bonus(s)
 }
 
 #[test]
@@ -80,3 +296,56 @@ fn test_solve() {
     assert_eq!(solve(s), 5);
     assert_eq!(bonus(s), 12);
 }
+
+#[test]
+fn test_any_slope() {
+    let segment = Segment::new((6, 5).into(), (0, 1).into());
+    assert_eq!(
+        segment.points().collect::<Vec<_>>(),
+        vec![(0, 1).into(), (3, 3).into(), (6, 5).into()]
+    );
+
+    // compare against counting every point, on a pseudo-random tangle of segments
+    let mut seed = 7u64;
+    let mut random = || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as i64 % 13
+    };
+
+    let segments = (0..60)
+        .map(|_| Segment::new((random(), random()).into(), (random(), random()).into()))
+        .collect::<Vec<_>>();
+
+    let mut covered = HashMap::<Pt, usize>::new();
+    for p in segments.iter().flat_map(Segment::points) {
+        *covered.entry(p).or_default() += 1;
+    }
+
+    for threshold in 1..=4 {
+        assert_eq!(
+            count_overlaps(&segments, threshold),
+            covered.values().filter(|&&c| c >= threshold).count()
+        );
+    }
+}
+
+#[test]
+fn test_huge() {
+    let n = 1_000_000_000_000;
+    let segments = [
+        Segment::new((0, 0).into(), (n, 0).into()),
+        Segment::new((n / 2, 0).into(), (2 * n, 0).into()),
+        Segment::new((0, -n).into(), (0, n).into()),
+        Segment::new((-n, -n).into(), (n, n).into()),
+    ];
+
+    assert_eq!(count_overlaps(&segments, 2), (n / 2 + 1) as usize + 1);
+    assert_eq!(count_overlaps(&segments, 3), 1);
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day05>();
+}
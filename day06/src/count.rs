@@ -0,0 +1,171 @@
+use std::fmt::{self, Display};
+
+/// Anything fish can be counted in.
+pub trait Count: Clone {
+    fn from_u64(n: u64) -> Self;
+    fn add(&self, other: &Self) -> Self;
+    fn mul(&self, other: &Self) -> Self;
+}
+
+/// Panics once the population no longer fits, rather than silently wrapping.
+impl Count for u128 {
+    fn from_u64(n: u64) -> Self {
+        n as u128
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self.checked_add(*other)
+            .expect("the population doesn't fit in a u128")
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        self.checked_mul(*other)
+            .expect("the population doesn't fit in a u128")
+    }
+}
+
+/// Counts modulo `P`, for when only the last few digits of a huge population matter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Modulo<const P: u64>(pub u64);
+
+impl<const P: u64> Count for Modulo<P> {
+    fn from_u64(n: u64) -> Self {
+        Modulo(n % P)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Modulo(((self.0 as u128 + other.0 as u128) % P as u128) as u64)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        Modulo(((self.0 as u128 * other.0 as u128) % P as u128) as u64)
+    }
+}
+
+impl<const P: u64> Display for Modulo<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An arbitrary-precision unsigned integer, with just enough arithmetic for counting fish.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BigUint {
+    /// Least significant first, without trailing zeros.
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    fn trim(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// `(self / d, self % d)`
+    pub fn div_rem(&self, d: u32) -> (Self, u32) {
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut rem = 0u64;
+
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            let n = rem << 32 | limb as u64;
+            quotient[i] = (n / d as u64) as u32;
+            rem = n % d as u64;
+        }
+
+        (BigUint { limbs: quotient }.trim(), rem as u32)
+    }
+}
+
+impl Count for BigUint {
+    fn from_u64(n: u64) -> Self {
+        BigUint {
+            limbs: vec![n as u32, (n >> 32) as u32],
+        }
+        .trim()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let mut limbs = Vec::with_capacity(self.limbs.len().max(other.limbs.len()) + 1);
+        let mut carry = 0u64;
+
+        for i in 0..self.limbs.len().max(other.limbs.len()) {
+            let a = *self.limbs.get(i).unwrap_or(&0) as u64;
+            let b = *other.limbs.get(i).unwrap_or(&0) as u64;
+            let sum = a + b + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+
+        limbs.push(carry as u32);
+        BigUint { limbs }.trim()
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let n = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+                limbs[i + j] = n as u32;
+                carry = n >> 32;
+            }
+
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+
+        BigUint { limbs }.trim()
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // nine decimal digits at a time, least significant first
+        let mut chunks = vec![];
+        let mut n = self.clone();
+        while !n.is_zero() {
+            let (q, r) = n.div_rem(1_000_000_000);
+            chunks.push(r);
+            n = q;
+        }
+
+        write!(f, "{}", chunks.pop().unwrap())?;
+        for chunk in chunks.iter().rev() {
+            write!(f, "{chunk:09}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_big() {
+    let two = BigUint::from_u64(2);
+    let big = (0..100).fold(BigUint::from_u64(1), |n, _| n.mul(&two));
+
+    assert_eq!(big.to_string(), "1267650600228229401496703205376");
+    assert_eq!(big.add(&big).div_rem(1 << 31).1, 0);
+    assert_eq!(
+        big.div_rem(1_000_000_007).1 as u128,
+        (1u128 << 100) % 1_000_000_007
+    );
+    assert_eq!(
+        BigUint::from_u64(u64::MAX)
+            .add(&BigUint::from_u64(1))
+            .to_string(),
+        "18446744073709551616"
+    );
+    assert_eq!(BigUint::from_u64(0).to_string(), "0");
+    assert_eq!(BigUint::from_u64(1_000_000_000).to_string(), "1000000000");
+}
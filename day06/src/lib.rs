@@ -1,25 +1,164 @@
-fn sim(s: &str, steps: u64) -> u64 {
-    let mut fish = vec![0; 9];
+mod count;
 
-    for k in s.trim().split(",").map(|s| s.parse::<usize>().unwrap()) {
-        fish[k] += 1;
+pub use count::*;
+use util::{parse_num, ParseError, Solution};
+
+/// A fish whose timer runs out starts over at `reset`, and a new one appears at `newborn`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timers {
+    pub reset: usize,
+    pub newborn: usize,
+}
+
+impl Default for Timers {
+    fn default() -> Self {
+        Timers {
+            reset: 6,
+            newborn: 8,
+        }
+    }
+}
+
+impl Timers {
+    /// Number of distinct timer values.
+    pub fn size(&self) -> usize {
+        self.reset.max(self.newborn) + 1
+    }
+}
+
+/// `rows[to][from]` is how many fish with timer `to` one fish with timer `from` becomes in a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Matrix<T> {
+    rows: Vec<Vec<T>>,
+}
+
+impl<T: Count> Matrix<T> {
+    fn identity(n: usize) -> Self {
+        Matrix {
+            rows: (0..n)
+                .map(|i| (0..n).map(|j| T::from_u64((i == j) as u64)).collect())
+                .collect(),
+        }
+    }
+
+    fn transition(timers: Timers) -> Self {
+        let n = timers.size();
+        let mut rows = vec![vec![0; n]; n];
+
+        for from in 1..n {
+            rows[from - 1][from] += 1;
+        }
+        rows[timers.reset][0] += 1;
+        rows[timers.newborn][0] += 1;
+
+        Matrix {
+            rows: rows
+                .into_iter()
+                .map(|row| row.into_iter().map(T::from_u64).collect())
+                .collect(),
+        }
     }
 
-    for _ in 0..steps {
-        let n = fish.remove(0);
-        fish[6] += n;
-        fish.push(n);
+    fn mul(&self, other: &Self) -> Self {
+        let n = self.rows.len();
+
+        Matrix {
+            rows: (0..n)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| {
+                            (0..n).fold(T::from_u64(0), |sum, k| {
+                                sum.add(&self.rows[i][k].mul(&other.rows[k][j]))
+                            })
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
     }
 
-    fish.iter().sum()
+    /// By repeated squaring, so in `O(n³ log e)`.
+    fn pow(&self, mut e: u64) -> Self {
+        let mut result = Self::identity(self.rows.len());
+        let mut base = self.clone();
+
+        while e > 0 {
+            if e & 1 == 1 {
+                result = result.mul(&base);
+            }
+            base = base.mul(&base);
+            e >>= 1;
+        }
+
+        result
+    }
 }
 
-pub fn solve(s: &str) -> u64 {
-    sim(s, 80)
+/// How many fish there are with each timer after `days` days, starting from `fish[timer]` fish
+/// with each timer.
+pub fn breakdown<T: Count>(fish: &[u64], days: u64, timers: Timers) -> Vec<T> {
+    assert!(
+        fish.len() <= timers.size(),
+        "timers only go up to {}",
+        timers.size() - 1
+    );
+
+    Matrix::<T>::transition(timers)
+        .pow(days)
+        .rows
+        .iter()
+        .map(|row| {
+            row.iter().zip(fish).fold(T::from_u64(0), |sum, (m, &n)| {
+                sum.add(&m.mul(&T::from_u64(n)))
+            })
+        })
+        .collect()
 }
 
-pub fn bonus(s: &str) -> u64 {
-    sim(s, 256)
+pub fn population<T: Count>(fish: &[u64], days: u64, timers: Timers) -> T {
+    breakdown::<T>(fish, days, timers)
+        .iter()
+        .fold(T::from_u64(0), |sum, n| sum.add(n))
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+
+    /// How many fish there are with each timer.
+    type Input = Vec<u64>;
+    type Part1 = u128;
+    type Part2 = u128;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let mut fish = vec![0; 9];
+
+        for timer in s.trim().split(',') {
+            match parse_num::<usize>(s, timer)? {
+                k @ 0..=8 => fish[k] += 1,
+                _ => return Err(ParseError::at(s, timer, "expected a timer from 0 to 8")),
+            }
+        }
+
+        Ok(fish)
+    }
+
+    fn part1(fish: &Self::Input) -> u128 {
+        population(fish, 80, Timers::default())
+    }
+
+    fn part2(fish: &Self::Input) -> u128 {
+        population(fish, 256, Timers::default())
+    }
+}
+
+pub fn solve(s: &str) -> u128 {
+    Day06::solve(s)
+}
+
+pub fn bonus(s: &str) -> u128 {
+    Day06::bonus(s)
 }
 
 #[test]
@@ -29,4 +168,61 @@ fn test_solve() {
 
     assert_eq!(solve(s), 5934);
     assert_eq!(bonus(s), 26984457539);
+
+    let fish = Day06::parse(s).unwrap();
+    assert_eq!(
+        breakdown::<u128>(&fish, 18, Timers::default()),
+        vec![3, 5, 3, 2, 2, 1, 5, 1, 4]
+    );
+}
+
+#[test]
+fn test_counts() {
+    let fish = Day06::parse("3,4,3,1,2").unwrap();
+    let timers = Timers::default();
+
+    let big = population::<BigUint>(&fish, 2000, timers);
+    let modulo = population::<Modulo<1_000_000_007>>(&fish, 2000, timers);
+    assert_eq!(big.div_rem(1_000_000_007).1 as u64, modulo.0);
+    assert_eq!(
+        population::<BigUint>(&fish, 256, timers).to_string(),
+        "26984457539"
+    );
+
+    // far too many fish to ever write down
+    let modulo = population::<Modulo<998_244_353>>(&fish, 1_000_000_000_000, timers);
+    assert!(modulo.0 < 998_244_353);
+}
+
+#[test]
+fn test_timers() {
+    let timers = Timers {
+        reset: 2,
+        newborn: 4,
+    };
+
+    // against simulating one fish at a time
+    let mut fish = vec![0, 3, 1];
+    for day in 0..30 {
+        let mut counts = vec![0; timers.size()];
+        for &timer in &fish {
+            counts[timer] += 1;
+        }
+        assert_eq!(breakdown::<u128>(&[1, 1, 0, 1], day, timers), counts);
+
+        let born = fish.iter().filter(|&&timer| timer == 0).count();
+        for timer in &mut fish {
+            *timer = if *timer == 0 {
+                timers.reset
+            } else {
+                *timer - 1
+            };
+        }
+        fish.extend(vec![timers.newborn; born]);
+    }
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day06>();
 }
@@ -1,28 +1,149 @@
-fn find(s: &str, dist: impl Fn(i32, i32) -> i32) -> i32 {
-    let nums = s
-        .trim()
-        .split(",")
-        .map(|s| s.parse::<i32>().unwrap())
-        .collect::<Vec<_>>();
+use std::collections::BTreeMap;
+use util::{parse_num, ParseError, Solution};
 
-    let min = *nums.iter().min().unwrap();
-    let max = *nums.iter().max().unwrap();
+/// Where to line up, and the fuel it takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alignment {
+    pub position: i64,
+    pub fuel: u64,
+}
 
-    (min..=max)
-        .map(|c| nums.iter().map(|&n| dist(c, n)).sum())
-        .min()
-        .unwrap()
+/// Crabs grouped by position, as `(position, count)` in order of position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crabs {
+    groups: Vec<(i64, u64)>,
 }
 
-pub fn solve(s: &str) -> i32 {
-    find(s, |a, b| (a - b).abs())
+impl Crabs {
+    pub fn new(groups: impl IntoIterator<Item = (i64, u64)>) -> Self {
+        let mut merged = BTreeMap::new();
+        for (position, count) in groups {
+            *merged.entry(position).or_insert(0) += count;
+        }
+
+        let groups = merged
+            .into_iter()
+            .filter(|&(_, count)| count > 0)
+            .collect::<Vec<_>>();
+        assert!(!groups.is_empty(), "there should be at least one crab");
+
+        Crabs { groups }
+    }
+
+    pub fn from_positions(positions: &[i64]) -> Self {
+        Self::new(positions.iter().map(|&position| (position, 1)))
+    }
+
+    /// Total fuel to line up at `target`, when moving `d` steps costs one crab `cost(d)`.
+    pub fn fuel(&self, target: i64, cost: impl Fn(u64) -> u64) -> u64 {
+        self.groups
+            .iter()
+            .map(|&(position, count)| count * cost(position.abs_diff(target)))
+            .sum()
+    }
+
+    fn align_at(&self, position: i64, cost: impl Fn(u64) -> u64) -> Alignment {
+        Alignment {
+            position,
+            fuel: self.fuel(position, cost),
+        }
+    }
+
+    /// With every step costing 1, the median is optimal.
+    pub fn align_linear(&self) -> Alignment {
+        let total = self.groups.iter().map(|&(_, count)| count).sum::<u64>();
+
+        let mut seen = 0;
+        let (median, _) = *self
+            .groups
+            .iter()
+            .find(|&&(_, count)| {
+                seen += count;
+                2 * seen >= total
+            })
+            .unwrap();
+
+        self.align_at(median, |d| d)
+    }
+
+    /// With the `n`th step costing `n`, the optimum is within half a step of the mean, so one of
+    /// the two positions around it.
+    pub fn align_triangular(&self) -> Alignment {
+        let (sum, total) =
+            self.groups
+                .iter()
+                .fold((0i128, 0i128), |(sum, total), &(position, count)| {
+                    (
+                        sum + position as i128 * count as i128,
+                        total + count as i128,
+                    )
+                });
+        let mean = sum.div_euclid(total) as i64;
+
+        [mean, mean + 1]
+            .map(|position| self.align_at(position, triangular))
+            .into_iter()
+            .min_by_key(|alignment| alignment.fuel)
+            .unwrap()
+    }
+
+    /// Works for any `cost` that is convex and never decreases, which makes the total fuel
+    /// convex in the position, so a binary search on its slope finds the lowest optimal position.
+    pub fn align_convex(&self, cost: impl Fn(u64) -> u64) -> Alignment {
+        let mut lo = self.groups[0].0;
+        let mut hi = self.groups[self.groups.len() - 1].0;
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.fuel(mid, &cost) <= self.fuel(mid + 1, &cost) {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+
+        self.align_at(lo, cost)
+    }
 }
 
-pub fn bonus(s: &str) -> i32 {
-    find(s, |a, b| {
-        let d = (a - b).abs();
-        (d * (d + 1)) / 2
-    })
+pub fn triangular(d: u64) -> u64 {
+    d * (d + 1) / 2
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+
+    type Input = Crabs;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let positions = s
+            .trim()
+            .split(',')
+            .map(|n| parse_num(s, n))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Crabs::from_positions(&positions))
+    }
+
+    fn part1(crabs: &Self::Input) -> u64 {
+        crabs.align_linear().fuel
+    }
+
+    fn part2(crabs: &Self::Input) -> u64 {
+        crabs.align_triangular().fuel
+    }
+}
+
+pub fn solve(s: &str) -> u64 {
+    Day07::solve(s)
+}
+
+pub fn bonus(s: &str) -> u64 {
+    Day07::bonus(s)
 }
 
 #[test]
@@ -33,8 +154,49 @@ fn test_solve() {
     assert_eq!(solve(s), 37);
     assert_eq!(bonus(s), 168);
 
-    let s = include_str!("../input.txt");
+    let crabs = Day07::parse(s).unwrap();
+    assert_eq!(
+        crabs.align_linear(),
+        Alignment {
+            position: 2,
+            fuel: 37
+        }
+    );
+    assert_eq!(
+        crabs.align_triangular(),
+        Alignment {
+            position: 5,
+            fuel: 168
+        }
+    );
+    assert_eq!(crabs.align_convex(|d| d).fuel, 37);
+    assert_eq!(crabs.align_convex(triangular), crabs.align_triangular());
+}
+
+#[test]
+fn test_weighted() {
+    let crabs = Crabs::new([(-3, 4), (10, 1), (2, 0), (7, 2), (-3, 1)]);
+    let positions = [-3, -3, -3, -3, -3, 10, 7, 7];
+    assert_eq!(crabs, Crabs::from_positions(&positions));
+
+    // against trying every position
+    let costs: [fn(u64) -> u64; 3] = [|d| d, triangular, |d| d * d * d];
+    for cost in costs {
+        let best = (-3..=10).map(|p| crabs.fuel(p, cost)).min().unwrap();
+        assert_eq!(crabs.align_convex(cost).fuel, best);
+    }
+
+    assert_eq!(
+        crabs.align_linear().fuel,
+        (-3..=10).map(|p| crabs.fuel(p, |d| d)).min().unwrap()
+    );
+    assert_eq!(
+        crabs.align_triangular().fuel,
+        (-3..=10).map(|p| crabs.fuel(p, triangular)).min().unwrap()
+    );
+}
 
-    assert_eq!(solve(s), 348664);
-    assert_eq!(bonus(s), 100220525);
+#[test]
+fn test_answers() {
+    util::verify::<Day07>();
 }
@@ -1,165 +1,180 @@
-#![feature(array_zip)]
+mod wiring;
 
-pub fn solve(s: &str) -> usize {
-    // 1 uses 2
-    // 4 uses 4
-    // 7 uses 3
-    // 8 uses 7
-
-    s.lines()
-        .map(|line| line.split_once(" | ").unwrap().1)
-        .flat_map(|line| line.split(" "))
-        .map(|code| code.len())
-        .filter(|&len| len == 2 || len == 4 || len == 3 || len == 7)
-        .count()
+use std::fmt::{self, Display};
+use util::{split_once, ParseError, Solution};
+pub use wiring::*;
+
+/// The patterns seen on one display, each a set of wires with wire `a` as the lowest bit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub patterns: Vec<u32>,
+    pub output: Vec<u32>,
+}
+
+impl Entry {
+    pub fn decode(&self, font: &Font) -> Result<String, WiringError> {
+        decode(font, &self.patterns, &self.output)
+    }
 }
 
-// how many? [a's, b's, c's, d's, e's, f's, g's]
-type Count = [usize; 7];
+fn parse_pattern(line: &str, pattern: &str) -> Result<u32, ParseError> {
+    let mut mask = 0;
 
-fn code_to_count(code: &str) -> Count {
-    let mut count = [0; 7];
+    for (i, c) in pattern.char_indices() {
+        if !c.is_ascii_lowercase() {
+            return Err(ParseError::at(
+                line,
+                &pattern[i..i + c.len_utf8()],
+                "expected a wire from a to z",
+            ));
+        }
 
-    for c in code.chars() {
-        let i = (c as usize) - ('a' as usize);
-        count[i] += 1;
+        mask |= 1 << (c as u8 - b'a');
     }
 
-    count
+    Ok(mask)
 }
 
-fn add_counts(x: Count, y: Count) -> Count {
-    x.zip(y).map(|(x, y)| x + y)
+fn parse_line(line: &str) -> Result<Entry, ParseError> {
+    let (patterns, output) = split_once(line, line, " | ")?;
+    let masks = |part: &str| {
+        part.split(' ')
+            .map(|pattern| parse_pattern(line, pattern))
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    Ok(Entry {
+        patterns: masks(patterns)?,
+        output: masks(output)?,
+    })
 }
 
-fn display([a, b, c, d, e, f, g]: Count) -> usize {
-    let sum = a + b + c + d + e + f + g;
-
-    if sum == 7 {
-        8
-    } else if sum == 2 {
-        1
-    } else if sum == 3 {
-        7
-    } else if sum == 4 {
-        4
-    } else if sum == 5 && b == 1 {
-        5
-    } else if sum == 5 && e == 1 {
-        2
-    } else if sum == 5 {
-        3
-    } else if sum == 6 && c == 0 {
-        6
-    } else if sum == 6 && d == 1 {
-        9
-    } else {
-        0
-    }
+/// The sum of every output, or the first entry that can't be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Total {
+    Sum(usize),
+    /// `line` is 1-based.
+    Unreadable {
+        line: usize,
+        error: WiringError,
+    },
 }
 
-fn decode(line: &str) -> usize {
-    let (pattern, message) = line.split_once(" | ").unwrap();
-
-    // incorrect -> count
-    let total_count = pattern
-        .split(" ")
-        .map(code_to_count)
-        .reduce(add_counts)
-        .unwrap();
-
-    //     a
-    //  b     c
-    //     d
-    //  e     f
-    //     g
-
-    let b = total_count.iter().position(|&k| k == 6).unwrap();
-    let e = total_count.iter().position(|&k| k == 4).unwrap();
-    let f = total_count.iter().position(|&k| k == 9).unwrap();
-
-    let mut one = pattern
-        .split(" ")
-        .find(|&code| code.len() == 2)
-        .map(code_to_count)
-        .unwrap();
-
-    one[f] -= 1;
-    let c = one.iter().position(|&k| k == 1).unwrap();
-
-    let a = total_count
-        .iter()
-        .enumerate()
-        .position(|(i, &k)| k == 8 && i != c)
-        .unwrap();
-
-    let mut four = pattern
-        .split(" ")
-        .find(|&code| code.len() == 4)
-        .map(code_to_count)
-        .unwrap();
-
-    four[b] -= 1;
-    four[c] -= 1;
-    four[f] -= 1;
-    let d = four.iter().position(|&k| k == 1).unwrap();
-
-    let g = (0..7)
-        .find(|&i| i != a && i != b && i != c && i != d && i != e && i != f)
-        .unwrap();
-
-    // incorrect -> correct
-    let mut found = [0; 7];
-    found[a] = 0;
-    found[b] = 1;
-    found[c] = 2;
-    found[d] = 3;
-    found[e] = 4;
-    found[f] = 5;
-    found[g] = 6;
-
-    let decode = |encoded: Count| {
-        let mut decoded = [0; 7];
-
-        for i in 0..7 {
-            if encoded[i] == 1 {
-                decoded[found[i]] = 1;
+impl Display for Total {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Total::Sum(sum) => write!(f, "{sum}"),
+            Total::Unreadable { line, error } => {
+                write!(f, "line {line}: can't read the display ({error})")
             }
         }
+    }
+}
 
-        decoded
-    };
+pub struct Day08;
 
-    let digits = message
-        .split(" ")
-        .map(code_to_count)
-        .map(decode)
-        .map(display)
-        .collect::<Vec<_>>();
+impl Solution for Day08 {
+    const DAY: u8 = 8;
 
-    let n = digits.len();
+    type Input = Vec<Entry>;
+    type Part1 = usize;
+    type Part2 = Total;
 
-    let output = digits
-        .iter()
-        .enumerate()
-        .map(|(i, &digit)| digit * 10usize.pow((n - i - 1) as u32))
-        .sum::<usize>();
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        s.lines()
+            .map(|line| parse_line(line).map_err(|err| err.within(s, line)))
+            .collect()
+    }
+
+    fn part1(entries: &Self::Input) -> usize {
+        let font = Font::seven_segment();
+
+        entries
+            .iter()
+            .flat_map(|entry| &entry.output)
+            .filter(|pattern| font.is_unique_size(pattern.count_ones()))
+            .count()
+    }
+
+    fn part2(entries: &Self::Input) -> Total {
+        let font = Font::seven_segment();
+        let mut sum = 0;
 
-    output
+        for (i, entry) in entries.iter().enumerate() {
+            match entry.decode(&font) {
+                // the seven-segment font only has digits
+                Ok(reading) => sum += reading.parse::<usize>().unwrap(),
+                Err(error) => return Total::Unreadable { line: i + 1, error },
+            }
+        }
+
+        Total::Sum(sum)
+    }
+}
+
+pub fn solve(s: &str) -> usize {
+    Day08::solve(s)
+}
+
+pub fn bonus(s: &str) -> Total {
+    Day08::bonus(s)
 }
 
 #[test]
 fn test_decode() {
+    let font = Font::seven_segment();
+    let entry = |line| parse_line(line).unwrap();
+
     assert_eq!(
-        decode(
+        entry(
             "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf"
-        ),
-        5353
+        )
+        .decode(&font),
+        Ok("5353".to_string())
     );
+
+    // two different ones
+    assert_eq!(
+        entry("ab cd | ab").decode(&font),
+        Err(WiringError::NoSolution)
+    );
+    // no wire past g
+    assert_eq!(entry("h | h").decode(&font), Err(WiringError::NoSolution));
+
+    // a 2, 3 or 5, with its five wires in any order and the other two either way round
+    let err = entry("abcde | abcde").decode(&font).unwrap_err();
+    assert_eq!(err, WiringError::Ambiguous(3 * 120 * 2));
+    assert_eq!(err.to_string(), "ambiguous: 720 candidates");
+
+    // every wiring reads a 1 the same
+    assert_eq!(entry("fa | af").decode(&font), Ok("1".to_string()));
 }
 
-pub fn bonus(s: &str) -> usize {
-    s.lines().map(decode).sum()
+#[test]
+fn test_fourteen_segment() {
+    let font = Font::fourteen_segment();
+    let glyphs = [
+        "abcdefkl", "bck", "abdegh", "abcdh", "bcfgh", "acdfgh", "acdefgh", "abc", "abcdefgh",
+        "abcdfgh", "abcefgh", "abcdhjm", "adef", "abcdjm", "adefg", "aefg",
+    ];
+
+    // segment `s` is driven by wire `wires[s]`
+    let wires = b"nbkdahmjcelgfi";
+    let scramble = |glyph: &str| {
+        glyph
+            .bytes()
+            .map(|b| wires[(b - b'a') as usize] as char)
+            .collect::<String>()
+    };
+
+    let patterns = glyphs.map(scramble).join(" ");
+    let output = [12, 0, 15, 15, 14].map(|i| scramble(glyphs[i])).join(" ");
+    let entry = parse_line(&format!("{patterns} | {output}")).unwrap();
+
+    // `i` and `n` are never lit and `j` and `m` always together, so neither pair can be told
+    // apart, but that doesn't change what anything reads
+    assert_eq!(wirings(&font, &entry.patterns).len(), 4);
+    assert_eq!(entry.decode(&font), Ok("C0FFE".to_string()));
 }
 
 #[test]
@@ -177,5 +192,25 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
 ";
 
     assert_eq!(solve(s), 26);
-    assert_eq!(bonus(s), 61229);
+    assert_eq!(bonus(s), Total::Sum(61229));
+
+    // unreadable displays only get in the way of part 2
+    let s = "fa | af\nabcde | abcde\n";
+    assert_eq!(solve(s), 1);
+    assert_eq!(
+        bonus(s).to_string(),
+        "line 2: can't read the display (ambiguous: 720 candidates)"
+    );
+    assert_eq!(
+        bonus("ab cd | ab"),
+        Total::Unreadable {
+            line: 1,
+            error: WiringError::NoSolution
+        }
+    );
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day08>();
 }
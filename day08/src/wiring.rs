@@ -0,0 +1,241 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+/// Which segments light up for each character, with segments numbered from `a`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Font {
+    segments: usize,
+    glyphs: Vec<(char, u32)>,
+}
+
+impl Font {
+    /// `glyphs` pairs every character with the letters of its segments, e.g. `('1', "cf")`.
+    pub fn new(segments: usize, glyphs: &[(char, &str)]) -> Self {
+        assert!(segments <= 26, "segments are named a to z");
+
+        let glyphs = glyphs
+            .iter()
+            .map(|&(c, lit)| {
+                let mask = lit.bytes().fold(0, |mask, b| {
+                    assert!(
+                        (b'a'..b'a' + segments as u8).contains(&b),
+                        "there's no segment {}",
+                        b as char
+                    );
+                    mask | 1 << (b - b'a')
+                });
+                (c, mask)
+            })
+            .collect();
+
+        Font { segments, glyphs }
+    }
+
+    /// ```text
+    ///  aaaa
+    /// b    c
+    /// b    c
+    ///  dddd
+    /// e    f
+    /// e    f
+    ///  gggg
+    /// ```
+    pub fn seven_segment() -> Self {
+        Self::new(
+            7,
+            &[
+                ('0', "abcefg"),
+                ('1', "cf"),
+                ('2', "acdeg"),
+                ('3', "acdfg"),
+                ('4', "bcdf"),
+                ('5', "abdfg"),
+                ('6', "abdefg"),
+                ('7', "acf"),
+                ('8', "abcdefg"),
+                ('9', "abcdfg"),
+            ],
+        )
+    }
+
+    /// Only the hexadecimal digits, as there's little agreement on the rest.
+    ///
+    /// ```text
+    ///  aaaaaaa
+    /// f i j k b
+    /// f  ijk  b
+    ///  ggg hhh
+    /// e  lmn  c
+    /// e l m n c
+    ///  ddddddd
+    /// ```
+    pub fn fourteen_segment() -> Self {
+        Self::new(
+            14,
+            &[
+                ('0', "abcdefkl"),
+                ('1', "bck"),
+                ('2', "abdegh"),
+                ('3', "abcdh"),
+                ('4', "bcfgh"),
+                ('5', "acdfgh"),
+                ('6', "acdefgh"),
+                ('7', "abc"),
+                ('8', "abcdefgh"),
+                ('9', "abcdfgh"),
+                ('A', "abcefgh"),
+                ('B', "abcdhjm"),
+                ('C', "adef"),
+                ('D', "abcdjm"),
+                ('E', "adefg"),
+                ('F', "aefg"),
+            ],
+        )
+    }
+
+    pub fn segments(&self) -> usize {
+        self.segments
+    }
+
+    /// Whether exactly one character lights up that many segments, so it can be recognized
+    /// without knowing the wiring.
+    pub fn is_unique_size(&self, segments: u32) -> bool {
+        self.glyphs
+            .iter()
+            .filter(|&&(_, mask)| mask.count_ones() == segments)
+            .count()
+            == 1
+    }
+
+    fn glyph(&self, mask: u32) -> Option<char> {
+        self.glyphs
+            .iter()
+            .find(|&&(_, glyph)| glyph == mask)
+            .map(|&(c, _)| c)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WiringError {
+    NoSolution,
+    /// Wirings that fit the patterns but read the output differently.
+    Ambiguous(usize),
+}
+
+impl Display for WiringError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WiringError::NoSolution => write!(f, "no solution"),
+            WiringError::Ambiguous(n) => write!(f, "ambiguous: {n} candidates"),
+        }
+    }
+}
+
+impl Error for WiringError {}
+
+/// `wiring[wire]` is the segment that wire lights up.
+pub type Wiring = Vec<usize>;
+
+fn rewire(wiring: &[usize], pattern: u32) -> u32 {
+    wiring
+        .iter()
+        .enumerate()
+        .filter(|&(wire, _)| pattern & 1 << wire != 0)
+        .fold(0, |mask, (_, &segment)| mask | 1 << segment)
+}
+
+/// Whether some glyph of the right size agrees with every wire assigned so far.
+fn fits(font: &Font, wiring: &[usize], pattern: u32) -> bool {
+    font.glyphs.iter().any(|&(_, glyph)| {
+        glyph.count_ones() == pattern.count_ones()
+            && wiring
+                .iter()
+                .enumerate()
+                .all(|(wire, &segment)| (pattern & 1 << wire != 0) == (glyph & 1 << segment != 0))
+    })
+}
+
+fn search(
+    font: &Font,
+    patterns: &[u32],
+    domains: &[u32],
+    wiring: &mut Wiring,
+    wirings: &mut Vec<Wiring>,
+) {
+    let wire = wiring.len();
+    if wire == font.segments {
+        wirings.push(wiring.clone());
+        return;
+    }
+
+    for segment in 0..font.segments {
+        if domains[wire] & 1 << segment == 0 || wiring.contains(&segment) {
+            continue;
+        }
+
+        wiring.push(segment);
+        if patterns.iter().all(|&pattern| fits(font, wiring, pattern)) {
+            search(font, patterns, domains, wiring, wirings);
+        }
+        wiring.pop();
+    }
+}
+
+/// Every wiring under which all of `patterns` are characters of `font`.
+///
+/// Before searching, each wire's possible segments are narrowed down by pattern size alone: a
+/// wire in a pattern of `n` segments has to light up a segment of some `n` segment glyph, and
+/// a wire outside of it a segment missing from one.
+pub fn wirings(font: &Font, patterns: &[u32]) -> Vec<Wiring> {
+    let n = font.segments;
+    if patterns.iter().any(|&pattern| pattern >> n != 0) {
+        return vec![];
+    }
+
+    let all = (1 << n) - 1;
+    let domains = (0..n)
+        .map(|wire| {
+            patterns.iter().fold(all, |domain, &pattern| {
+                let same_size = font
+                    .glyphs
+                    .iter()
+                    .filter(|&&(_, glyph)| glyph.count_ones() == pattern.count_ones());
+
+                let possible = if pattern & 1 << wire != 0 {
+                    same_size.fold(0, |mask, &(_, glyph)| mask | glyph)
+                } else {
+                    same_size.fold(0, |mask, &(_, glyph)| mask | !glyph & all)
+                };
+
+                domain & possible
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut found = vec![];
+    search(font, patterns, &domains, &mut vec![], &mut found);
+    found
+}
+
+/// Reads `output` once `patterns` and the output itself have pinned down the wiring well enough
+/// that every fitting wiring reads it the same way.
+pub fn decode(font: &Font, patterns: &[u32], output: &[u32]) -> Result<String, WiringError> {
+    let all = patterns.iter().chain(output).copied().collect::<Vec<_>>();
+    let wirings = wirings(font, &all);
+
+    let read = |wiring: &Wiring| {
+        output
+            .iter()
+            .map(|&pattern| font.glyph(rewire(wiring, pattern)).unwrap())
+            .collect::<String>()
+    };
+
+    let first = read(wirings.first().ok_or(WiringError::NoSolution)?);
+    if wirings.iter().skip(1).any(|wiring| read(wiring) != first) {
+        return Err(WiringError::Ambiguous(wirings.len()));
+    }
+
+    Ok(first)
+}
@@ -0,0 +1,163 @@
+use util::grid::{Grid, Pos};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Only through the sides of a cell.
+    Four,
+    /// Through the corners too.
+    Eight,
+}
+
+/// Disjoint sets of cell indices, with union by size and path halving.
+struct DisjointSets {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        DisjointSets {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+
+        let (small, large) = if self.size[a] < self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = large;
+        self.size[large] += self.size[small];
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Basin {
+    /// The lowest cell, or the first of them in reading order.
+    pub low_point: Pos,
+    pub size: usize,
+}
+
+/// Every cell labelled with the basin it belongs to, numbered in reading order of their first
+/// cell, or `None` for walls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Basins {
+    pub labels: Grid<Option<usize>>,
+    pub basins: Vec<Basin>,
+}
+
+impl Basins {
+    pub fn new(
+        heightmap: &Grid<usize>,
+        is_wall: impl Fn(usize) -> bool,
+        connectivity: Connectivity,
+    ) -> Self {
+        let width = heightmap.width();
+        let index = |(x, y): Pos| y * width + x;
+        let mut sets = DisjointSets::new(width * heightmap.height());
+
+        for p in heightmap.positions() {
+            if is_wall(heightmap[p]) {
+                continue;
+            }
+
+            let neighbors = match connectivity {
+                Connectivity::Four => heightmap.neighbors4(p).collect::<Vec<_>>(),
+                Connectivity::Eight => heightmap.neighbors8(p).collect(),
+            };
+            for n in neighbors {
+                if !is_wall(heightmap[n]) {
+                    sets.union(index(p), index(n));
+                }
+            }
+        }
+
+        let mut label_of_root = vec![None; width * heightmap.height()];
+        let mut basins = vec![];
+        let labels = Grid::new(width, heightmap.height(), |p| {
+            if is_wall(heightmap[p]) {
+                return None;
+            }
+
+            let root = sets.find(index(p));
+            let label = *label_of_root[root].get_or_insert_with(|| {
+                basins.push(Basin {
+                    low_point: p,
+                    size: 0,
+                });
+                basins.len() - 1
+            });
+
+            let basin = &mut basins[label];
+            basin.size += 1;
+            if heightmap[p] < heightmap[basin.low_point] {
+                basin.low_point = p;
+            }
+
+            Some(label)
+        });
+
+        Basins { labels, basins }
+    }
+
+    /// Largest first.
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = self
+            .basins
+            .iter()
+            .map(|basin| basin.size)
+            .collect::<Vec<_>>();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// One letter per basin, cycling through `a` to `z` and `A` to `Z`, and `#` for walls.
+    pub fn render(&self) -> String {
+        const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+        self.labels.render(|label| match label {
+            Some(label) => LETTERS[label % LETTERS.len()] as char,
+            None => '#',
+        })
+    }
+
+    /// A binary PPM image with one pixel per cell, each basin in its own colour and walls black.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!(
+            "P6\n{} {}\n255\n",
+            self.labels.width(),
+            self.labels.height()
+        )
+        .into_bytes();
+
+        for label in self.labels.values() {
+            ppm.extend(match *label {
+                Some(label) => colour(label),
+                None => [0, 0, 0],
+            });
+        }
+
+        ppm
+    }
+}
+
+/// Spreads consecutive labels far apart, and never quite to black.
+fn colour(label: usize) -> [u8; 3] {
+    let hash = (label as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    [hash >> 56, hash >> 48, hash >> 40].map(|c| c as u8 | 0x40)
+}
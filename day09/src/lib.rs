@@ -1,144 +1,48 @@
-use std::{
-    cmp::Reverse,
-    collections::HashSet,
-    ops::{Index, IndexMut},
-    str::FromStr,
-};
-
-type Pos = (usize, usize);
-
-struct Grid<T> {
-    data: Vec<Vec<T>>,
-    width: usize,
-    height: usize,
-}
+mod basins;
 
-impl<T> Grid<T> {
-    fn neighbors(&self, (x, y): Pos) -> Vec<Pos> {
-        let mut neighbors = vec![];
-        if x > 0 {
-            neighbors.push((x - 1, y));
-        }
-        if y > 0 {
-            neighbors.push((x, y - 1));
-        }
-        if x < self.width - 1 {
-            neighbors.push((x + 1, y));
-        }
-        if y < self.height - 1 {
-            neighbors.push((x, y + 1));
-        }
-        neighbors
-    }
+pub use basins::*;
+use util::{grid::Grid, ParseError, Solution};
 
-    fn map<S>(&self, mut f: impl FnMut(&T) -> S) -> Grid<S> {
-        Grid {
-            width: self.width,
-            height: self.height,
-            data: self
-                .data
-                .iter()
-                .map(|line| line.iter().map(|value| f(value)).collect())
-                .collect(),
-        }
-    }
-}
+pub struct Day09;
 
-impl<T> Index<Pos> for Grid<T> {
-    type Output = T;
+impl Solution for Day09 {
+    const DAY: u8 = 9;
 
-    fn index(&self, (x, y): Pos) -> &Self::Output {
-        &self.data[y][x]
-    }
-}
-
-impl<T> IndexMut<Pos> for Grid<T> {
-    fn index_mut(&mut self, (x, y): Pos) -> &mut Self::Output {
-        &mut self.data[y][x]
-    }
-}
+    type Input = Grid<usize>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-impl FromStr for Grid<usize> {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let data = s
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| c as usize - '0' as usize)
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-
-        let height = data.len();
-        let width = data[0].len();
-
-        Ok(Self {
-            data,
-            width,
-            height,
-        })
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        s.parse()
     }
-}
-
-pub fn solve(s: &str) -> usize {
-    let heightmap: Grid<usize> = s.parse().unwrap();
 
-    let mut risk = 0;
+    fn part1(heightmap: &Self::Input) -> usize {
+        let mut risk = 0;
 
-    for y in 0..heightmap.height {
-        for x in 0..heightmap.width {
-            let p = (x, y);
-
-            if heightmap
-                .neighbors(p)
-                .iter()
-                .all(|&n| heightmap[p] < heightmap[n])
-            {
+        for p in heightmap.positions() {
+            if heightmap.neighbors4(p).all(|n| heightmap[p] < heightmap[n]) {
                 risk += heightmap[p] + 1;
             }
         }
+
+        risk
     }
 
-    risk
+    fn part2(heightmap: &Self::Input) -> usize {
+        Basins::new(heightmap, |height| height == 9, Connectivity::Four)
+            .sizes()
+            .iter()
+            .take(3)
+            .product()
+    }
 }
 
-pub fn bonus(s: &str) -> usize {
-    let heightmap: Grid<usize> = s.parse().unwrap();
-
-    let mut mapped = heightmap.map(|&height| height == 9);
-    let mut basin_sizes: Vec<Reverse<usize>> = vec![];
-
-    for y in 0..heightmap.height {
-        for x in 0..heightmap.width {
-            let p = (x, y);
-
-            if !mapped[p] {
-                // discover new basin greedily
-                let mut basin = HashSet::new();
-                let mut queue = vec![p];
-                while let Some(p) = queue.pop() {
-                    if basin.insert(p) {
-                        mapped[p] = true;
-                        for n in heightmap.neighbors(p) {
-                            if !mapped[n] {
-                                queue.push(n);
-                            }
-                        }
-                    }
-                }
-
-                basin_sizes.push(Reverse(basin.len()));
-                basin_sizes.sort();
-                if basin_sizes.len() > 3 {
-                    basin_sizes.remove(3);
-                }
-            }
-        }
-    }
+pub fn solve(s: &str) -> usize {
+    Day09::solve(s)
+}
 
-    basin_sizes.iter().fold(1, |m, a| m * a.0)
+pub fn bonus(s: &str) -> usize {
+    Day09::bonus(s)
 }
 
 #[test]
@@ -151,4 +55,54 @@ fn test_solve() {
 
     assert_eq!(solve(s), 15);
     assert_eq!(bonus(s), 1134);
+
+    let basins = Basins::new(&Day09::parse(s).unwrap(), |h| h == 9, Connectivity::Four);
+    assert_eq!(basins.sizes(), vec![14, 9, 9, 3]);
+    assert_eq!(
+        basins
+            .basins
+            .iter()
+            .map(|basin| basin.low_point)
+            .collect::<Vec<_>>(),
+        vec![(1, 0), (9, 0), (2, 2), (6, 4)]
+    );
+    assert_eq!(
+        basins.render(),
+        "aa###bbbbb
+a#ccc#b#bb
+#ccccc#d#b
+ccccc#ddd#
+#c###ddddd"
+    );
+
+    let ppm = basins.to_ppm();
+    assert!(ppm.starts_with(b"P6\n10 5\n255\n"));
+    assert_eq!(ppm.len(), 12 + 3 * 50);
+}
+
+#[test]
+fn test_connectivity() {
+    let heightmap = Day09::parse(
+        "1911
+9199
+1999",
+    )
+    .unwrap();
+
+    let four = Basins::new(&heightmap, |h| h == 9, Connectivity::Four);
+    assert_eq!(four.render(), "a#bb\n#c##\nd###");
+    assert_eq!(four.sizes(), vec![2, 1, 1, 1]);
+
+    let eight = Basins::new(&heightmap, |h| h == 9, Connectivity::Eight);
+    assert_eq!(eight.render(), "a#aa\n#a##\na###");
+    assert_eq!(eight.sizes(), vec![5]);
+
+    // anything from 2 up is a wall
+    let low = Basins::new(&heightmap, |h| h >= 2, Connectivity::Four);
+    assert_eq!(low, four);
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day09>();
 }
@@ -0,0 +1,195 @@
+use std::io::{self, BufRead, Write};
+
+/// An opening delimiter that's still waiting for its closer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Open {
+    /// Byte offset in the line.
+    pub offset: usize,
+    /// Index into the checker's pairs.
+    pub pair: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    Balanced,
+    /// The closer of pair `found` at byte `offset` doesn't close `expected`, which is `None` if
+    /// nothing was open.
+    Corrupted {
+        offset: usize,
+        found: usize,
+        expected: Option<Open>,
+    },
+    /// The line ends with `unclosed` still open, outermost first, and appending `completion`
+    /// would close them.
+    Incomplete {
+        unclosed: Vec<Open>,
+        completion: String,
+    },
+}
+
+/// Checks that delimiters are properly nested. Delimiters can be any non-empty strings, the
+/// longest one wins where several match, and any other text is skipped.
+///
+/// A delimiter that both opens and closes, like a quote, closes if it's what the innermost open
+/// delimiter is waiting for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checker {
+    pairs: Vec<(String, String)>,
+}
+
+impl Checker {
+    pub fn new<O: Into<String>, C: Into<String>>(pairs: impl IntoIterator<Item = (O, C)>) -> Self {
+        let pairs = pairs
+            .into_iter()
+            .map(|(open, close)| (open.into(), close.into()))
+            .collect::<Vec<_>>();
+        assert!(
+            pairs
+                .iter()
+                .all(|(open, close)| !open.is_empty() && !close.is_empty()),
+            "delimiters can't be empty"
+        );
+
+        Checker { pairs }
+    }
+
+    /// `()`, `[]`, `{}` and `<>`, in that order.
+    pub fn brackets() -> Self {
+        Self::new([("(", ")"), ("[", "]"), ("{", "}"), ("<", ">")])
+    }
+
+    pub fn open(&self, pair: usize) -> &str {
+        &self.pairs[pair].0
+    }
+
+    pub fn close(&self, pair: usize) -> &str {
+        &self.pairs[pair].1
+    }
+
+    /// The longest delimiter at the start of `rest`.
+    fn delimiter_at<'a>(&'a self, rest: &str) -> Option<&'a str> {
+        self.pairs
+            .iter()
+            .flat_map(|(open, close)| [open, close])
+            .filter(|delimiter| rest.starts_with(delimiter.as_str()))
+            .max_by_key(|delimiter| delimiter.len())
+            .map(String::as_str)
+    }
+
+    pub fn check(&self, line: &str) -> Diagnostic {
+        let mut stack: Vec<Open> = vec![];
+        let mut offset = 0;
+
+        while offset < line.len() {
+            let Some(delimiter) = self.delimiter_at(&line[offset..]) else {
+                offset += line[offset..].chars().next().unwrap().len_utf8();
+                continue;
+            };
+
+            let expected = stack.last().copied();
+            if expected.map_or(false, |open| self.close(open.pair) == delimiter) {
+                stack.pop();
+            } else if let Some(pair) = self.pairs.iter().position(|(open, _)| open == delimiter) {
+                stack.push(Open { offset, pair });
+            } else {
+                let found = self
+                    .pairs
+                    .iter()
+                    .position(|(_, close)| close == delimiter)
+                    .unwrap();
+
+                return Diagnostic::Corrupted {
+                    offset,
+                    found,
+                    expected,
+                };
+            }
+
+            offset += delimiter.len();
+        }
+
+        if stack.is_empty() {
+            return Diagnostic::Balanced;
+        }
+
+        let completion = stack
+            .iter()
+            .rev()
+            .map(|open| self.close(open.pair))
+            .collect();
+        Diagnostic::Incomplete {
+            unclosed: stack,
+            completion,
+        }
+    }
+
+    /// Checks one line at a time, so the whole input never has to be in memory.
+    pub fn check_lines<'a>(
+        &'a self,
+        reader: impl BufRead + 'a,
+    ) -> impl Iterator<Item = io::Result<(String, Diagnostic)>> + 'a {
+        reader.lines().map(move |line| {
+            let line = line?;
+            let diagnostic = self.check(&line);
+            Ok((line, diagnostic))
+        })
+    }
+
+    /// Writes a `path:line:column: error: ...` message for every line that isn't balanced, with
+    /// columns counted in characters from 1, and returns how many there were.
+    pub fn report(
+        &self,
+        path: &str,
+        reader: impl BufRead,
+        mut out: impl Write,
+    ) -> io::Result<usize> {
+        let mut errors = 0;
+
+        for (i, result) in self.check_lines(reader).enumerate() {
+            let (line, diagnostic) = result?;
+            let row = i + 1;
+            let column = |offset: usize| line[..offset].chars().count() + 1;
+
+            match diagnostic {
+                Diagnostic::Balanced => continue,
+                Diagnostic::Corrupted {
+                    offset,
+                    found,
+                    expected: Some(open),
+                } => writeln!(
+                    out,
+                    "{path}:{row}:{}: error: mismatched closing delimiter `{}`, expected `{}` to close `{}` at {row}:{}",
+                    column(offset),
+                    self.close(found),
+                    self.close(open.pair),
+                    self.open(open.pair),
+                    column(open.offset),
+                )?,
+                Diagnostic::Corrupted {
+                    offset,
+                    found,
+                    expected: None,
+                } => writeln!(
+                    out,
+                    "{path}:{row}:{}: error: unexpected closing delimiter `{}`",
+                    column(offset),
+                    self.close(found),
+                )?,
+                Diagnostic::Incomplete {
+                    unclosed,
+                    completion,
+                } => writeln!(
+                    out,
+                    "{path}:{row}:{}: error: unclosed delimiter `{}` opened at {row}:{}, expected `{completion}`",
+                    column(line.len()),
+                    self.open(unclosed[unclosed.len() - 1].pair),
+                    column(unclosed[unclosed.len() - 1].offset),
+                )?,
+            }
+
+            errors += 1;
+        }
+
+        Ok(errors)
+    }
+}
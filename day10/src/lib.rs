@@ -1,71 +1,80 @@
-fn score(c: char) -> usize {
-    match c {
-        ')' => 3,
-        ']' => 57,
-        '}' => 1197,
-        '>' => 25137,
-        _ => unreachable!(),
+mod checker;
+
+pub use checker::*;
+use util::{ParseError, Solution};
+
+/// By pair, in the order of `Checker::brackets`.
+const SYNTAX_ERROR_SCORES: [usize; 4] = [3, 57, 1197, 25137];
+const COMPLETION_SCORES: [usize; 4] = [1, 2, 3, 4];
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<String>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        s.lines()
+            .map(
+                |line| match line.char_indices().find(|&(_, c)| !"()[]{}<>".contains(c)) {
+                    Some((i, c)) => Err(ParseError::at(
+                        s,
+                        &line[i..i + c.len_utf8()],
+                        "expected a bracket",
+                    )),
+                    None => Ok(line.to_string()),
+                },
+            )
+            .collect()
     }
-}
 
-fn line_score(line: &str) -> (usize, Vec<char>) {
-    let mut stack = vec![];
-
-    for c in line.chars() {
-        match c {
-            '(' => stack.push(')'),
-            '[' => stack.push(']'),
-            '{' => stack.push('}'),
-            '<' => stack.push('>'),
-            c => {
-                if stack.pop() != Some(c) {
-                    return (score(c), stack);
-                }
-            }
-        }
+    fn part1(lines: &Self::Input) -> usize {
+        let checker = Checker::brackets();
+
+        lines
+            .iter()
+            .map(|line| match checker.check(line) {
+                Diagnostic::Corrupted { found, .. } => SYNTAX_ERROR_SCORES[found],
+                _ => 0,
+            })
+            .sum()
     }
 
-    (0, stack)
-}
+    /// 0 if no line is incomplete.
+    fn part2(lines: &Self::Input) -> usize {
+        let checker = Checker::brackets();
 
-pub fn solve(s: &str) -> usize {
-    s.lines().map(line_score).map(|p| p.0).sum()
-}
+        let mut scores = lines
+            .iter()
+            .filter_map(|line| match checker.check(line) {
+                Diagnostic::Incomplete { unclosed, .. } => Some(
+                    unclosed
+                        .iter()
+                        .rev()
+                        .fold(0, |score, open| score * 5 + COMPLETION_SCORES[open.pair]),
+                ),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if scores.is_empty() {
+            return 0;
+        }
 
-fn autocomplete_score(c: char) -> usize {
-    match c {
-        ')' => 1,
-        ']' => 2,
-        '}' => 3,
-        '>' => 4,
-        _ => unreachable!(),
+        scores.sort_unstable();
+        scores[scores.len() / 2]
     }
 }
 
+pub fn solve(s: &str) -> usize {
+    Day10::solve(s)
+}
+
 pub fn bonus(s: &str) -> usize {
-    let mut autocomplete_scores = s
-        .lines()
-        .map(line_score)
-        .filter_map(|(score, mut leftover)| {
-            if score == 0 && leftover.len() > 0 {
-                leftover.reverse();
-                Some(leftover)
-            } else {
-                None
-            }
-        })
-        .map(|leftover| {
-            let mut score = 0;
-            for c in leftover {
-                score = (score * 5) + autocomplete_score(c);
-            }
-            score
-        })
-        .collect::<Vec<_>>();
-
-    autocomplete_scores.sort();
-
-    autocomplete_scores[autocomplete_scores.len() / 2]
+    Day10::bonus(s)
 }
 
 #[test]
@@ -83,4 +92,77 @@ fn test_solve() {
 
     assert_eq!(solve(s), 26397);
     assert_eq!(bonus(s), 288957);
+
+    let checker = Checker::brackets();
+    assert_eq!(
+        checker.check("{([(<{}[<>[]}>{[]{[(<()>"),
+        Diagnostic::Corrupted {
+            offset: 12,
+            found: 2,
+            expected: Some(Open { offset: 7, pair: 1 })
+        }
+    );
+    assert_eq!(
+        checker.check("[({(<(())[]>[[{[]{<()<>>"),
+        Diagnostic::Incomplete {
+            unclosed: [0, 1, 2, 3, 12, 13, 14, 17]
+                .iter()
+                .zip([1, 0, 2, 0, 1, 1, 2, 2])
+                .map(|(&offset, pair)| Open { offset, pair })
+                .collect(),
+            completion: "}}]])})]".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_no_incomplete() {
+    assert_eq!(bonus("()\n[>"), 0);
+    assert_eq!(solve("()\n[>"), 25137);
+}
+
+#[test]
+fn test_checker() {
+    let checker = Checker::new([("begin", "end"), ("(*", "*)"), ("(", ")"), ("\"", "\"")]);
+
+    assert_eq!(
+        checker.check("begin f(\"x\", (* y *)) end"),
+        Diagnostic::Balanced
+    );
+    assert_eq!(
+        checker.check("begin (* end"),
+        Diagnostic::Corrupted {
+            offset: 9,
+            found: 0,
+            expected: Some(Open { offset: 6, pair: 1 })
+        }
+    );
+    assert_eq!(
+        checker.check(") begin"),
+        Diagnostic::Corrupted {
+            offset: 0,
+            found: 2,
+            expected: None
+        }
+    );
+    assert!(matches!(
+        checker.check("begin \"(ü"),
+        Diagnostic::Incomplete { completion, .. } if completion == ")\"end"
+    ));
+
+    let s = "begin end\nbegin ü)\n(\"\n\n)\n";
+    let mut out = vec![];
+    assert_eq!(checker.report("a.pas", s.as_bytes(), &mut out).unwrap(), 3);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "a.pas:2:8: error: mismatched closing delimiter `)`, expected `end` to close `begin` at 2:1
+a.pas:3:3: error: unclosed delimiter `\"` opened at 3:2, expected `\")`
+a.pas:5:1: error: unexpected closing delimiter `)`
+"
+    );
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day10>();
 }
@@ -1,11 +1,48 @@
 use day10::*;
+use std::{
+    env,
+    fs::File,
+    io::{self, BufReader},
+    process,
+};
 use util::*;
 
+/// `day10 check [<path|->...]` reports unbalanced brackets in any files, or stdin if there are
+/// none, instead of solving.
 fn main() {
-    let s = include_str!("../input.txt");
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let [command, paths @ ..] = args.as_slice() else {
+        return run_day::<Day10>();
+    };
+    if command != "check" {
+        return run_day::<Day10>();
+    }
 
-    time(|| {
-        println!("Solution: {}", solve(s));
-        println!("Bonus: {}", bonus(s));
-    });
+    let stdin = ["-".to_string()];
+    let paths = if paths.is_empty() { &stdin } else { paths };
+
+    let checker = Checker::brackets();
+    let mut errors = 0;
+
+    for path in paths {
+        let result = if path == "-" {
+            checker.report("<stdin>", io::stdin().lock(), io::stdout().lock())
+        } else {
+            File::open(path)
+                .and_then(|file| checker.report(path, BufReader::new(file), io::stdout().lock()))
+        };
+
+        match result {
+            Ok(n) => errors += n,
+            Err(err) => {
+                let name = if path == "-" { "<stdin>" } else { path };
+                eprintln!("error: {name}: {err}");
+                process::exit(2);
+            }
+        }
+    }
+
+    if errors > 0 {
+        process::exit(1);
+    }
 }
@@ -0,0 +1,148 @@
+use std::{collections::HashMap, iter};
+use util::grid::{Grid, Pos, DIRS8};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Neighbors past the edge don't exist.
+    Bounded,
+    /// Neighbors past the edge are on the opposite side.
+    Toroidal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// A cell flashes once its energy reaches this, and then goes back to 0.
+    pub threshold: usize,
+    /// Energy every cell gains at the start of a step.
+    pub gain: usize,
+    /// Energy a flash gives each cell in its neighborhood.
+    pub spread: usize,
+    /// Offsets from a flashing cell to the cells it gives energy to.
+    pub neighborhood: Vec<(isize, isize)>,
+    pub topology: Topology,
+}
+
+/// Dumbo octopuses.
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            threshold: 10,
+            gain: 1,
+            spread: 1,
+            neighborhood: DIRS8.to_vec(),
+            topology: Topology::Bounded,
+        }
+    }
+}
+
+/// After `start` steps, the state repeats every `length` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Automaton {
+    pub rules: Rules,
+    pub energy: Grid<usize>,
+    /// Steps taken so far.
+    pub steps: usize,
+}
+
+impl Automaton {
+    pub fn new(energy: Grid<usize>, rules: Rules) -> Self {
+        assert!(rules.threshold > 0, "cells would flash forever");
+
+        Automaton {
+            rules,
+            energy,
+            steps: 0,
+        }
+    }
+
+    fn neighborhood(&self, p: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.rules
+            .neighborhood
+            .iter()
+            .filter_map(move |&d| match self.rules.topology {
+                Topology::Bounded => self.energy.offset(p, d),
+                Topology::Toroidal => Some(self.energy.wrapping_offset(p, d)),
+            })
+    }
+
+    /// Returns how many cells flashed. Each cell flashes at most once a step, so flashes cascade
+    /// until no cell that hasn't flashed yet has reached the threshold.
+    pub fn step(&mut self) -> usize {
+        let threshold = self.rules.threshold;
+        let mut flashed = self.energy.map(|_| false);
+        let mut todo = vec![];
+
+        for p in self.energy.positions() {
+            self.energy[p] += self.rules.gain;
+            if self.energy[p] >= threshold {
+                flashed[p] = true;
+                todo.push(p);
+            }
+        }
+
+        let mut flashes = 0;
+        while let Some(p) = todo.pop() {
+            flashes += 1;
+
+            let neighbors = self.neighborhood(p).collect::<Vec<_>>();
+            for n in neighbors {
+                self.energy[n] += self.rules.spread;
+                if !flashed[n] && self.energy[n] >= threshold {
+                    flashed[n] = true;
+                    todo.push(n);
+                }
+            }
+        }
+
+        for p in self.energy.positions() {
+            if flashed[p] {
+                self.energy[p] = 0;
+            }
+        }
+
+        self.steps += 1;
+        flashes
+    }
+
+    /// How many cells flash in each step from now on.
+    pub fn flashes(&mut self) -> impl Iterator<Item = usize> + '_ {
+        iter::repeat_with(|| self.step())
+    }
+
+    /// Steps until the state repeats. Energy stays below the threshold between steps, so it
+    /// always does eventually.
+    pub fn find_cycle(&mut self) -> Cycle {
+        let mut seen = HashMap::new();
+
+        loop {
+            if let Some(start) = seen.insert(self.energy.clone(), self.steps) {
+                return Cycle {
+                    start,
+                    length: self.steps - start,
+                };
+            }
+            self.step();
+        }
+    }
+
+    /// The first step in which every cell flashes, or `None` if the state starts repeating
+    /// before that ever happens.
+    pub fn first_synchronized(&mut self) -> Option<usize> {
+        let cells = self.energy.width() * self.energy.height();
+        let mut seen = HashMap::new();
+
+        while seen.insert(self.energy.clone(), self.steps).is_none() {
+            if self.step() == cells {
+                return Some(self.steps);
+            }
+        }
+
+        None
+    }
+}
@@ -1,133 +1,64 @@
-use std::{
-    ops::{Index, IndexMut},
-    str::FromStr,
-};
-
-type Pos = (usize, usize);
-
-#[derive(Debug)]
-struct Grid<T> {
-    data: Vec<Vec<T>>,
-    width: usize,
-    height: usize,
-}
+mod automaton;
 
-impl<T> Grid<T> {
-    fn neighbors(&self, (x, y): Pos) -> Vec<Pos> {
-        let mut neighbors = vec![];
-        if y > 0 && x > 0 {
-            neighbors.push((x - 1, y - 1));
-        }
-        if y > 0 {
-            neighbors.push((x, y - 1));
-        }
-        if y > 0 && x < self.width - 1 {
-            neighbors.push((x + 1, y - 1));
-        }
-        if x > 0 {
-            neighbors.push((x - 1, y));
-        }
-        if x < self.width - 1 {
-            neighbors.push((x + 1, y));
-        }
-        if y < self.height - 1 && x > 0 {
-            neighbors.push((x - 1, y + 1));
-        }
-        if y < self.height - 1 {
-            neighbors.push((x, y + 1));
-        }
-        if y < self.height - 1 && x < self.width - 1 {
-            neighbors.push((x + 1, y + 1));
-        }
-        neighbors
-    }
-}
-
-impl<T> Index<Pos> for Grid<T> {
-    type Output = T;
+pub use automaton::*;
+use std::fmt::{self, Display};
+use util::{grid::Grid, ParseError, Solution};
 
-    fn index(&self, (x, y): Pos) -> &Self::Output {
-        &self.data[y][x]
-    }
+/// The first step in which every octopus flashes, if the state repeats before that.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Step(usize),
+    Never,
 }
 
-impl<T> IndexMut<Pos> for Grid<T> {
-    fn index_mut(&mut self, (x, y): Pos) -> &mut Self::Output {
-        &mut self.data[y][x]
+impl From<Option<usize>> for Outcome {
+    fn from(step: Option<usize>) -> Self {
+        step.map_or(Outcome::Never, Outcome::Step)
     }
 }
 
-impl FromStr for Grid<usize> {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let data = s
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| c as usize - '0' as usize)
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-
-        let height = data.len();
-        let width = data[0].len();
-
-        Ok(Self {
-            data,
-            width,
-            height,
-        })
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Step(step) => write!(f, "{step}"),
+            Outcome::Never => write!(f, "never"),
+        }
     }
 }
 
-fn step(octos: &mut Grid<usize>) -> usize {
-    let mut flashes = 0;
+pub struct Day11;
 
-    let mut todo = (0..octos.height)
-        .flat_map(|y| (0..octos.width).map(move |x| (x, y)))
-        .collect::<Vec<_>>();
+impl Solution for Day11 {
+    const DAY: u8 = 11;
 
-    while let Some(p) = todo.pop() {
-        octos[p] += 1;
+    type Input = Grid<usize>;
+    type Part1 = usize;
+    type Part2 = Outcome;
 
-        if octos[p] == 10 {
-            flashes += 1;
-            for n in octos.neighbors(p) {
-                todo.push(n);
-            }
-        }
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        s.parse()
     }
 
-    for y in 0..octos.height {
-        for x in 0..octos.width {
-            let p = (x, y);
-            if octos[p] > 9 {
-                octos[p] = 0;
-            }
-        }
+    fn part1(octos: &Self::Input) -> usize {
+        Automaton::new(octos.clone(), Rules::default())
+            .flashes()
+            .take(100)
+            .sum()
     }
 
-    flashes
+    fn part2(octos: &Self::Input) -> Outcome {
+        Automaton::new(octos.clone(), Rules::default())
+            .first_synchronized()
+            .into()
+    }
 }
 
 pub fn solve(s: &str) -> usize {
-    let mut octos: Grid<usize> = s.parse().unwrap();
-
-    let mut total_flashes = 0;
-
-    for _ in 0..100 {
-        total_flashes += step(&mut octos);
-    }
-
-    total_flashes
+    Day11::solve(s)
 }
 
-pub fn bonus(s: &str) -> usize {
-    let mut octos: Grid<usize> = s.parse().unwrap();
-    let n = octos.width * octos.height;
-
-    (1..).find(|_| n == step(&mut octos)).unwrap()
+pub fn bonus(s: &str) -> Outcome {
+    Day11::bonus(s)
 }
 
 #[test]
@@ -144,5 +75,86 @@ fn test_solve() {
 5283751526";
 
     assert_eq!(solve(s), 1656);
-    assert_eq!(bonus(s), 195);
+    assert_eq!(bonus(s), Outcome::Step(195));
+
+    let mut octos = Automaton::new(Day11::parse(s).unwrap(), Rules::default());
+    assert_eq!(
+        octos.flashes().take(10).collect::<Vec<_>>(),
+        vec![0, 35, 45, 16, 8, 1, 7, 24, 39, 29]
+    );
+
+    // once in sync they stay in sync, with everyone flashing every 10 steps
+    assert_eq!(octos.first_synchronized(), Some(195));
+    assert_eq!(
+        octos.find_cycle(),
+        Cycle {
+            start: 195,
+            length: 10
+        }
+    );
+}
+
+#[test]
+fn test_rules() {
+    let small = Day11::parse(
+        "12
+30",
+    )
+    .unwrap();
+
+    let rules = Rules {
+        threshold: 4,
+        gain: 1,
+        spread: 2,
+        neighborhood: vec![(1, 0)],
+        topology: Topology::Toroidal,
+    };
+    let mut automaton = Automaton::new(small.clone(), rules.clone());
+    // the 3 flashes, giving 2 to the 0 on its right
+    assert_eq!(automaton.step(), 1);
+    assert_eq!(
+        automaton.energy,
+        Grid::from_rows(vec![vec![2, 3], vec![0, 3]])
+    );
+    // both 3s flash, and the top one wraps around to set off the top left
+    assert_eq!(automaton.step(), 3);
+    assert_eq!(
+        automaton.energy,
+        Grid::from_rows(vec![vec![0, 0], vec![3, 0]])
+    );
+
+    let bounded = Rules {
+        topology: Topology::Bounded,
+        ..rules
+    };
+    let mut automaton = Automaton::new(small, bounded);
+    assert_eq!(automaton.flashes().take(2).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(
+        automaton.energy,
+        Grid::from_rows(vec![vec![3, 0], vec![1, 0]])
+    );
+
+    // nothing ever flashes, so the state after a step is back where it started
+    let mut still = Automaton::new(
+        Grid::from_rows(vec![vec![1]]),
+        Rules {
+            gain: 0,
+            ..Rules::default()
+        },
+    );
+    let never = Outcome::from(still.first_synchronized());
+    assert_eq!(never, Outcome::Never);
+    assert_eq!(never.to_string(), "never");
+    assert_eq!(
+        still.find_cycle(),
+        Cycle {
+            start: 1,
+            length: 1
+        }
+    );
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day11>();
 }
@@ -1,60 +1,248 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use util::{
+    search::{count_paths, Graph},
+    split_once, ParseError, Solution,
+};
 
-fn find_paths(s: &str, allow_double_visit: bool) -> usize {
-    let mut adj: HashMap<&str, HashSet<&str>> = HashMap::new();
-    let mut is_small = HashMap::new();
+/// Caves numbered in order of appearance, so a set of them fits in a `u64`.
+pub struct Caves {
+    names: Vec<String>,
+    /// Neighbors in order of name.
+    adj: Vec<Vec<usize>>,
+    is_small: Vec<bool>,
+    start: usize,
+    end: usize,
+}
+
+fn is_small(name: &str) -> bool {
+    name.chars().all(|c| c.is_ascii_lowercase())
+}
+
+fn parse(s: &str) -> Result<Caves, ParseError> {
+    let mut ids = HashMap::new();
+    let mut names = vec![];
+
+    let mut id = |name: &str| {
+        *ids.entry(name.to_string()).or_insert_with(|| {
+            names.push(name.to_string());
+            names.len() - 1
+        })
+    };
 
+    let mut edges = vec![];
     for line in s.lines() {
-        let (a, b) = line.split_once("-").unwrap();
+        let (a, b) = split_once(s, line, "-")?;
+        // going back and forth between them would make endless paths
+        if !is_small(a) && !is_small(b) {
+            return Err(ParseError::at(
+                s,
+                line,
+                "expected a small cave at one end of the passage",
+            ));
+        }
 
-        is_small.insert(a, a.chars().all(|c| c.is_ascii_lowercase()));
-        is_small.insert(b, b.chars().all(|c| c.is_ascii_lowercase()));
+        let (a, b) = (id(a), id(b));
+        if a.max(b) >= 64 {
+            return Err(ParseError::at(
+                s,
+                line,
+                "expected at most 64 caves, to track visits in a u64",
+            ));
+        }
+        edges.push((a, b));
+    }
 
-        adj.entry(a)
-            .and_modify(|dsts| {
-                dsts.insert(b);
-            })
-            .or_insert_with(|| HashSet::from([b]));
+    for cave in ["start", "end"] {
+        if !ids.contains_key(cave) {
+            return Err(ParseError::at(
+                s,
+                &s[s.len()..],
+                format!("expected a passage to `{cave}`"),
+            ));
+        }
+    }
+    let (start, end) = (ids["start"], ids["end"]);
+
+    let mut adj = vec![vec![]; names.len()];
+    for (a, b) in edges {
+        adj[a].push(b);
+        adj[b].push(a);
+    }
+    // a passage listed twice is still only one way to go
+    for neighbors in &mut adj {
+        neighbors.sort_unstable_by_key(|&cave| &names[cave]);
+        neighbors.dedup();
+    }
+
+    Ok(Caves {
+        is_small: names.iter().map(|name| is_small(name)).collect(),
+        names,
+        adj,
+        start,
+        end,
+    })
+}
 
-        adj.entry(b)
-            .and_modify(|dsts| {
-                dsts.insert(a);
+/// Where we are, which small caves we've been through, and how many more times we can go back
+/// into one of them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Visit {
+    at: usize,
+    visited: u64,
+    revisits: usize,
+}
+
+struct Routes<'a> {
+    caves: &'a Caves,
+}
+
+impl Graph for Routes<'_> {
+    type Node = Visit;
+    type Cost = usize;
+
+    /// In order of name.
+    fn neighbors(&self, visit: &Visit) -> Vec<(Visit, usize)> {
+        let Caves {
+            adj,
+            is_small,
+            start,
+            end,
+            ..
+        } = self.caves;
+
+        if visit.at == *end {
+            return vec![];
+        }
+
+        adj[visit.at]
+            .iter()
+            .filter(|&&dest| dest != *start)
+            .filter_map(|&dest| {
+                let mut next = Visit {
+                    at: dest,
+                    ..visit.clone()
+                };
+
+                if is_small[dest] {
+                    if visit.visited & (1 << dest) == 0 {
+                        next.visited |= 1 << dest;
+                    } else if visit.revisits > 0 {
+                        next.revisits -= 1;
+                    } else {
+                        return None;
+                    }
+                }
+
+                Some((next, 1))
             })
-            .or_insert_with(|| HashSet::from([a]));
+            .collect()
+    }
+}
+
+impl Caves {
+    pub fn name(&self, cave: usize) -> &str {
+        &self.names[cave]
+    }
+
+    fn first_visit(&self, revisits: usize) -> Visit {
+        Visit {
+            at: self.start,
+            visited: 1 << self.start,
+            revisits,
+        }
+    }
+
+    /// Paths from `start` to `end` that go into small caves they've already been through at most
+    /// `revisits` times in total, and never back to `start`.
+    pub fn count_paths(&self, revisits: usize) -> usize {
+        count_paths(
+            &Routes { caves: self },
+            self.first_visit(revisits),
+            |visit| visit.at == self.end,
+        )
     }
 
-    let mut completed = 0;
-
-    let mut todo = vec![(HashMap::new(), "start", false)];
-
-    while let Some((visited, at, has_double_visit)) = todo.pop() {
-        for &dest in &adj[at] {
-            if dest == "end" {
-                completed += 1;
-            } else if !is_small[dest] || !visited.contains_key(&dest) {
-                let mut visited = visited.clone();
-                visited.insert(at, 1);
-                todo.push((visited, dest, has_double_visit));
-            } else if allow_double_visit
-                && !has_double_visit
-                && (dest != "start" && visited[dest] < 2)
-            {
-                let mut visited = visited.clone();
-                visited.insert(at, 2);
-                todo.push((visited, dest, true));
+    /// The same paths as [`Caves::count_paths`] counts, in lexicographic order of cave names.
+    /// There are very many of them for all but small cave systems.
+    pub fn paths(&self, revisits: usize) -> Paths {
+        Paths {
+            routes: Routes { caves: self },
+            stack: vec![(None, vec![self.first_visit(revisits)])],
+        }
+    }
+}
+
+pub struct Paths<'a> {
+    routes: Routes<'a>,
+    /// The path so far, each cave with the visits left to try from it, last one first.
+    stack: Vec<(Option<usize>, Vec<Visit>)>,
+}
+
+impl<'a> Iterator for Paths<'a> {
+    type Item = Vec<&'a str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let caves = self.routes.caves;
+
+        while let Some((_, todo)) = self.stack.last_mut() {
+            let Some(visit) = todo.pop() else {
+                self.stack.pop();
+                continue;
+            };
+
+            let mut next = self
+                .routes
+                .neighbors(&visit)
+                .into_iter()
+                .map(|(next, _)| next)
+                .collect::<Vec<_>>();
+            next.reverse();
+
+            let at = visit.at;
+            self.stack.push((Some(at), next));
+
+            if at == caves.end {
+                return Some(
+                    self.stack
+                        .iter()
+                        .filter_map(|&(cave, _)| cave.map(|cave| caves.name(cave)))
+                        .collect(),
+                );
             }
         }
+
+        None
     }
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = Caves;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    completed
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part1(caves: &Self::Input) -> usize {
+        caves.count_paths(0)
+    }
+
+    fn part2(caves: &Self::Input) -> usize {
+        caves.count_paths(1)
+    }
 }
 
 pub fn solve(s: &str) -> usize {
-    find_paths(s, false)
+    Day12::solve(s)
 }
 
 pub fn bonus(s: &str) -> usize {
-    find_paths(s, true)
+    Day12::bonus(s)
 }
 
 #[test]
@@ -71,6 +259,26 @@ b-end
     assert_eq!(solve(s), 10);
     assert_eq!(bonus(s), 36);
 
+    let caves = Day12::parse(s).unwrap();
+    assert_eq!(
+        caves
+            .paths(0)
+            .map(|path| path.join(","))
+            .collect::<Vec<_>>(),
+        vec![
+            "start,A,b,A,c,A,end",
+            "start,A,b,A,end",
+            "start,A,b,end",
+            "start,A,c,A,b,A,end",
+            "start,A,c,A,b,end",
+            "start,A,c,A,end",
+            "start,A,end",
+            "start,b,A,c,A,end",
+            "start,b,A,end",
+            "start,b,end",
+        ]
+    );
+
     let s = "dc-end
 HN-start
 start-kj
@@ -86,6 +294,13 @@ kj-dc
     assert_eq!(solve(s), 19);
     assert_eq!(bonus(s), 103);
 
+    let caves = Day12::parse(s).unwrap();
+    for revisits in 0..4 {
+        let paths = caves.paths(revisits).collect::<Vec<_>>();
+        assert_eq!(paths.len(), caves.count_paths(revisits));
+        assert!(paths.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
     let s = "fs-end
 he-DX
 fs-he
@@ -108,3 +323,37 @@ start-RW";
     assert_eq!(solve(s), 226);
     assert_eq!(bonus(s), 3509);
 }
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        Day12::parse("start-A\nA-b").err().unwrap().to_string(),
+        "line 2, column 4: expected a passage to `end`, found the end of the input"
+    );
+    assert!(Day12::parse("A-end").is_err());
+    assert_eq!(
+        Day12::parse("start-A\nA-B\nB-end")
+            .err()
+            .unwrap()
+            .to_string(),
+        "line 2, column 1: expected a small cave at one end of the passage, found `A-B`"
+    );
+
+    let many = (0..70).map(|i| format!("start-c{i}\n")).collect::<String>();
+    assert_eq!(
+        Day12::parse(&many).err().unwrap().to_string(),
+        "line 64, column 1: expected at most 64 caves, to track visits in a u64, found `start-c63`"
+    );
+
+    let s = "start-A\nA-end\nA-end\nend-A\nstart-A";
+    assert_eq!(solve(s), 1);
+    assert_eq!(
+        Day12::parse(s).unwrap().paths(1).collect::<Vec<_>>(),
+        vec![vec!["start", "A", "end"]]
+    );
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day12>();
+}
@@ -1,41 +1,46 @@
 #![feature(int_roundings)]
 
 use std::collections::HashSet;
+use util::{parse_num, split_once, ParseError, Solution};
 
 #[derive(Debug)]
-enum Instruction {
+pub enum Instruction {
     FoldAlongX(usize),
     FoldAlongY(usize),
 }
 
 type Pos = (usize, usize);
 
-fn parse(s: &str) -> (HashSet<Pos>, Vec<Instruction>) {
-    let (dots, instructions) = s.split_once("\n\n").unwrap();
+fn parse(s: &str) -> Result<(HashSet<Pos>, Vec<Instruction>), ParseError> {
+    let (dots, instructions) = split_once(s, s, "\n\n")?;
 
     let dots = dots
         .lines()
         .map(|line| {
-            let (x, y) = line.split_once(",").unwrap();
-            (x.parse().unwrap(), y.parse().unwrap())
+            let (x, y) = split_once(s, line, ",")?;
+            Ok((parse_num(s, x)?, parse_num(s, y)?))
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
     let instructions = instructions
         .lines()
         .map(|line| {
-            let (intro, n) = line.split_once("=").unwrap();
-            let n = n.parse::<usize>().unwrap();
-
-            if intro == "fold along y" {
-                Instruction::FoldAlongY(n)
-            } else {
-                Instruction::FoldAlongX(n)
+            let (intro, n) = split_once(s, line, "=")?;
+            let n = parse_num(s, n)?;
+
+            match intro {
+                "fold along x" => Ok(Instruction::FoldAlongX(n)),
+                "fold along y" => Ok(Instruction::FoldAlongY(n)),
+                _ => Err(ParseError::at(
+                    s,
+                    intro,
+                    "expected `fold along x` or `fold along y`",
+                )),
             }
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    (dots, instructions)
+    Ok((dots, instructions))
 }
 
 fn fold(dots: HashSet<Pos>, instruction: &Instruction) -> HashSet<Pos> {
@@ -59,16 +64,8 @@ fn fold(dots: HashSet<Pos>, instruction: &Instruction) -> HashSet<Pos> {
         .collect()
 }
 
-pub fn solve(s: &str) -> usize {
-    let (dots, instructions) = parse(s);
-
-    let dots = fold(dots, &instructions[0]);
-
-    dots.len()
-}
-
 /// Definitely not complete, and also just plain wrong, but for my input it works good enough :P
-fn detect(grid: &Vec<Vec<char>>, x: usize) -> char {
+fn detect(grid: &[Vec<char>], x: usize) -> char {
     //
     // #### ###  #    #  # ###  ###  #### #  #
     // #    #  # #    #  # #  # #  # #    #  #
@@ -133,24 +130,52 @@ fn detect(grid: &Vec<Vec<char>>, x: usize) -> char {
     '?'
 }
 
-pub fn bonus(s: &str) -> String {
-    let (mut dots, instructions) = parse(s);
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
 
-    for instruction in instructions {
-        dots = fold(dots, &instruction);
+    type Input = (HashSet<Pos>, Vec<Instruction>);
+    type Part1 = usize;
+    type Part2 = String;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
     }
 
-    let xmax = dots.iter().map(|p| p.0).max().unwrap();
-    let ymax = dots.iter().map(|p| p.1).max().unwrap();
+    fn part1((dots, instructions): &Self::Input) -> usize {
+        let dots = fold(dots.clone(), &instructions[0]);
 
-    let mut grid = vec![vec![' '; xmax + 2]; ymax + 2];
-    for (x, y) in dots {
-        grid[y][x] = '#';
+        dots.len()
     }
 
-    (0..(xmax.div_ceil(5)))
-        .map(|x| detect(&grid, x * 5))
-        .collect()
+    fn part2((dots, instructions): &Self::Input) -> String {
+        let mut dots = dots.clone();
+
+        for instruction in instructions {
+            dots = fold(dots, instruction);
+        }
+
+        let xmax = dots.iter().map(|p| p.0).max().unwrap();
+        let ymax = dots.iter().map(|p| p.1).max().unwrap();
+
+        let mut grid = vec![vec![' '; xmax + 2]; ymax + 2];
+        for (x, y) in dots {
+            grid[y][x] = '#';
+        }
+
+        (0..(xmax.div_ceil(5)))
+            .map(|x| detect(&grid, x * 5))
+            .collect()
+    }
+}
+
+pub fn solve(s: &str) -> usize {
+    Day13::solve(s)
+}
+
+pub fn bonus(s: &str) -> String {
+    Day13::bonus(s)
 }
 
 #[test]
@@ -181,3 +206,8 @@ fold along x=5
     assert_eq!(solve(s), 17);
     assert_eq!(bonus(s), "O".to_string());
 }
+
+#[test]
+fn test_answers() {
+    util::verify::<Day13>();
+}
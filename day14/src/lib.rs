@@ -1,8 +1,9 @@
 use itertools::Itertools;
-use std::{cell::RefCell, collections::HashMap, iter::Sum};
+use std::{collections::HashMap, iter::Sum};
+use util::{split_once, Memo, ParseError, Solution};
 
-type Polymer = Vec<(char, char)>;
-type Rules = HashMap<(char, char), char>;
+pub type Polymer = Vec<(char, char)>;
+pub type Rules = HashMap<(char, char), char>;
 
 #[derive(Debug, Clone)]
 struct Histo(HashMap<char, u64>);
@@ -50,74 +51,74 @@ fn add_histos(mut x: Histo, y: Histo) -> Histo {
     x
 }
 
-fn parse(s: &str) -> (Polymer, Rules) {
-    let (template, rules) = s.split_once("\n\n").unwrap();
+fn parse(s: &str) -> Result<(Polymer, Rules), ParseError> {
+    let (template, rules) = split_once(s, s, "\n\n")?;
     let template = template
         .chars()
         .tuple_windows::<(char, char)>()
         .collect::<Vec<_>>();
 
+    if template.is_empty() {
+        return Err(ParseError::at(
+            s,
+            s,
+            "expected a template of at least 2 elements",
+        ));
+    }
+
     let rules = rules
         .trim()
         .lines()
         .map(|line| {
-            let (pair, new) = line.split_once(" -> ").unwrap();
-            let pair = pair.chars().collect::<Vec<_>>();
-            let pair = (pair[0], pair[1]);
-            let new = new.chars().nth(0).unwrap();
-            (pair, new)
+            let (pair, new) = split_once(s, line, " -> ")?;
+
+            match (pair.chars().collect_tuple(), new.chars().collect_tuple()) {
+                (Some(pair), Some((new,))) => Ok((pair, new)),
+                (None, _) => Err(ParseError::at(s, pair, "expected a pair of elements")),
+                (_, None) => Err(ParseError::at(s, new, "expected a single element")),
+            }
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    (template, rules)
+    Ok((template, rules))
 }
 
-std::thread_local! {
-  static CALC_MEMO: RefCell<HashMap<(char, char, usize), Histo>> = RefCell::new(HashMap::new());
+/// Expands pairs with one set of rules. The memo only lives as long as those rules, so it
+/// doesn't need them in its key.
+struct Expander<'a> {
+    rules: &'a Rules,
+    memo: Memo<(char, char, usize), Histo>,
 }
 
-fn calc(le: char, ri: char, steps: usize, rules: &Rules) -> Histo {
-    if steps == 0 {
-        return Histo::from([(le, 1)]);
-    } else if let Some(&insert) = rules.get(&(le, ri)) {
-        return add_histos(
-            calc_memoized(le, insert, steps - 1, rules),
-            calc_memoized(insert, ri, steps - 1, rules),
-        );
-    }
-
-    Histo::from([(le, 1)])
-}
+impl Expander<'_> {
+    /// Counts of everything `le` grows into after `steps`, up to but not including `ri`
+    fn calc(&self, le: char, ri: char, steps: usize) -> Histo {
+        if steps == 0 {
+            return Histo::from([(le, 1)]);
+        }
 
-/// Basically just copied what `#[memoize]` generates, except omitting `rules`. Technically incorrect for that reason, if other `rules` would ever be used.
-fn calc_memoized(le: char, ri: char, steps: usize, rules: &Rules) -> Histo {
-    let r = CALC_MEMO.with(|cell| {
-        let memo = cell.borrow_mut();
-        memo.get(&(le, ri, steps)).cloned()
-    });
+        let Some(&insert) = self.rules.get(&(le, ri)) else {
+            return Histo::from([(le, 1)]);
+        };
 
-    if let Some(r) = r {
-        return r;
+        self.memo.get_or_insert_with((le, ri, steps), || {
+            add_histos(
+                self.calc(le, insert, steps - 1),
+                self.calc(insert, ri, steps - 1),
+            )
+        })
     }
-
-    let r = calc(le, ri, steps, rules);
-
-    CALC_MEMO.with(|cell| {
-        let mut memo = cell.borrow_mut();
-        memo.insert((le, ri, steps), r.clone());
-    });
-
-    r
 }
 
-fn run(template: Polymer, rules: Rules, steps: usize) -> Histo {
-    CALC_MEMO.with(|cell| {
-        cell.borrow_mut().clear();
-    });
+fn run(template: &Polymer, rules: &Rules, steps: usize) -> Histo {
+    let expander = Expander {
+        rules,
+        memo: Memo::new(),
+    };
 
     let mut histo = template
         .iter()
-        .map(|&(le, ri)| calc_memoized(le, ri, steps, &rules))
+        .map(|&(le, ri)| expander.calc(le, ri, steps))
         .sum::<Histo>();
 
     let last_char = template.last().unwrap().1;
@@ -133,20 +134,38 @@ fn score(histo: Histo) -> u64 {
     histo.last().unwrap().1 - histo.first().unwrap().1
 }
 
-pub fn solve(s: &str) -> u64 {
-    let (template, rules) = parse(s);
+pub struct Day14;
 
-    let histo = run(template, rules, 10);
+impl Solution for Day14 {
+    const DAY: u8 = 14;
 
-    score(histo)
-}
+    type Input = (Polymer, Rules);
+    type Part1 = u64;
+    type Part2 = u64;
 
-pub fn bonus(s: &str) -> u64 {
-    let (template, rules) = parse(s);
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
 
-    let histo = run(template, rules, 40);
+    fn part1((template, rules): &Self::Input) -> u64 {
+        let histo = run(template, rules, 10);
 
-    score(histo)
+        score(histo)
+    }
+
+    fn part2((template, rules): &Self::Input) -> u64 {
+        let histo = run(template, rules, 40);
+
+        score(histo)
+    }
+}
+
+pub fn solve(s: &str) -> u64 {
+    Day14::solve(s)
+}
+
+pub fn bonus(s: &str) -> u64 {
+    Day14::bonus(s)
 }
 
 #[test]
@@ -171,10 +190,11 @@ CC -> N
 CN -> C
 ";
 
-    let actual = include_str!("../input.txt");
-
     assert_eq!(solve(s), 1588);
-    assert_eq!(solve(actual), 2703);
     assert_eq!(bonus(s), 2188189693529);
-    assert_eq!(bonus(actual), 2984946368465);
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day14>();
 }
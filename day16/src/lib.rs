@@ -1,4 +1,5 @@
 use std::fmt::Debug;
+use util::{ParseError, Solution};
 
 use parse::{hextobin, parse_packet};
 
@@ -50,16 +51,43 @@ impl Packet {
     }
 }
 
-pub fn solve(s: &str) -> usize {
-    let packet = parse_packet(&hextobin(s.trim()));
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+
+    type Input = Packet;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let hex = s.trim();
+        let bits = hextobin(hex).map_err(|err| err.within(s, hex))?;
+
+        parse_packet(&bits).map_err(|err| {
+            // point at the hex digit holding the offending bit
+            let bit = err.column - 1;
+            let at = (bit / 4).min(hex.len());
+            let digit = &hex[at..(at + 1).min(hex.len())];
+            ParseError::at(s, digit, format!("{} (at bit {bit})", err.message))
+        })
+    }
+
+    fn part1(packet: &Self::Input) -> usize {
+        packet.fold(0, &|total, p| total + p.version)
+    }
 
-    packet.fold(0, &|total, p| total + p.version)
+    fn part2(packet: &Self::Input) -> usize {
+        packet.eval()
+    }
 }
 
-pub fn bonus(s: &str) -> usize {
-    let packet = parse_packet(&hextobin(s.trim()));
+pub fn solve(s: &str) -> usize {
+    Day16::solve(s)
+}
 
-    packet.eval()
+pub fn bonus(s: &str) -> usize {
+    Day16::bonus(s)
 }
 
 #[test]
@@ -81,3 +109,8 @@ fn test_bonus() {
     assert_eq!(bonus("9C005AC2F8F0"), 0);
     assert_eq!(bonus("9C0141080250320F1802104A08"), 1);
 }
+
+#[test]
+fn test_answers() {
+    util::verify::<Day16>();
+}
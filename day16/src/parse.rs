@@ -3,11 +3,12 @@ use nom::{
     branch::alt,
     character::complete::{char, one_of},
     combinator::{map, verify},
-    error::Error,
+    error::{Error, ErrorKind},
     multi::{count, many0},
     sequence::{pair, preceded, tuple},
     IResult, Parser,
 };
+use util::ParseError;
 
 fn bitvec_to_num(v: Vec<char>) -> usize {
     let s = v.iter().collect::<String>();
@@ -59,7 +60,7 @@ fn p_literal(s: &str) -> IResult<&str, Packet> {
     )(s)
 }
 
-fn packet_children<'a>(s: &'a str) -> IResult<&'a str, Vec<Packet>> {
+fn packet_children(s: &str) -> IResult<&str, Vec<Packet>> {
     let (s, len_type_id) = one_of("01")(s)?;
     match len_type_id {
         '0' => children_upto_len(s),
@@ -68,13 +69,16 @@ fn packet_children<'a>(s: &'a str) -> IResult<&'a str, Vec<Packet>> {
     }
 }
 
-fn children_upto_len<'a>(s: &'a str) -> IResult<&'a str, Vec<Packet>> {
+fn children_upto_len(s: &str) -> IResult<&str, Vec<Packet>> {
     let (s, bitlen) = map(bits(15), bitvec_to_num)(s)?;
+    if bitlen > s.len() {
+        return Err(nom::Err::Error(Error::new(&s[s.len()..], ErrorKind::Eof)));
+    }
     let (_, children) = many0(p_packet)(&s[0..bitlen])?;
     Ok((&s[bitlen..], children))
 }
 
-fn children_upto_amount<'a>(s: &'a str) -> IResult<&'a str, Vec<Packet>> {
+fn children_upto_amount(s: &str) -> IResult<&str, Vec<Packet>> {
     let (mut s, num) = map(bits(11), bitvec_to_num)(s)?;
 
     let mut children = vec![];
@@ -104,33 +108,36 @@ fn p_packet(s: &str) -> IResult<&str, Packet> {
     alt((p_literal, p_operator))(s)
 }
 
-pub fn parse_packet(s: &str) -> Packet {
-    p_packet(s).unwrap().1
+/// Errors point into the string of bits.
+pub fn parse_packet(s: &str) -> Result<Packet, ParseError> {
+    match p_packet(s) {
+        Ok((_, packet)) => Ok(packet),
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+            Err(ParseError::at(s, err.input, "expected a packet"))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::at(s, &s[s.len()..], "expected a packet")),
+    }
 }
 
-pub fn hextobin(s: &str) -> String {
-    s.chars()
-        .map(|c| {
-            format!(
-                "{:01$b}",
-                if c.is_numeric() {
-                    (c as usize) - ('0' as usize)
-                } else {
-                    (c as usize) - ('A' as usize) + 10
-                },
-                4
-            )
+pub fn hextobin(s: &str) -> Result<String, ParseError> {
+    s.char_indices()
+        .map(|(i, c)| {
+            let digit = c.to_digit(16).ok_or_else(|| {
+                ParseError::at(s, &s[i..i + c.len_utf8()], "expected a hexadecimal digit")
+            })?;
+
+            Ok(format!("{digit:04b}"))
         })
-        .collect::<Vec<_>>()
-        .join("")
+        .collect()
 }
 
 #[test]
 fn test_hextobin() {
     assert_eq!(
         hextobin("EE00D40C823060"),
-        "11101110000000001101010000001100100000100011000001100000".to_string()
+        Ok("11101110000000001101010000001100100000100011000001100000".to_string())
     );
+    assert!(hextobin("EE0Z").is_err());
 }
 
 #[test]
@@ -208,7 +215,7 @@ fn test_parse() {
 
     assert_eq!(
         parse_packet("11101110000000001101010000001100100000100011000001100000"),
-        Packet {
+        Ok(Packet {
             version: 7,
             type_id: 3,
             number: 0,
@@ -232,6 +239,7 @@ fn test_parse() {
                     children: vec![]
                 }
             ]
-        }
+        })
     );
+    assert!(parse_packet("1110111000000000110101").is_err());
 }
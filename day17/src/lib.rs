@@ -1,6 +1,6 @@
-use tuple::Map;
+use util::{parse_num, split_once, ParseError, Solution};
 
-type InclusiveBounds = ((i32, i32), (i32, i32));
+pub type InclusiveBounds = ((i32, i32), (i32, i32));
 
 type Pos = (i32, i32);
 type Velocity = (i32, i32);
@@ -9,21 +9,16 @@ fn within((x, y): Pos, ((xmin, xmax), (ymin, ymax)): InclusiveBounds) -> bool {
     xmin <= x && x <= xmax && ymin <= y && y <= ymax
 }
 
-fn parse(s: &str) -> InclusiveBounds {
-    let pieces = s.trim().split("=").collect::<Vec<&str>>();
-    let y_range = pieces[2];
-    let x_range = pieces[1].split_once(",").unwrap().0;
+fn parse(s: &str) -> Result<InclusiveBounds, ParseError> {
+    let (_, ranges) = split_once(s, s.trim(), "x=")?;
+    let (x_range, y_range) = split_once(s, ranges, ", y=")?;
 
-    (
-        x_range
-            .split_once("..")
-            .unwrap()
-            .map(|p| p.parse::<i32>().unwrap()),
-        y_range
-            .split_once("..")
-            .unwrap()
-            .map(|p| p.parse::<i32>().unwrap()),
-    )
+    let range = |r| {
+        let (lo, hi) = split_once(s, r, "..")?;
+        Ok((parse_num(s, lo)?, parse_num(s, hi)?))
+    };
+
+    Ok((range(x_range)?, range(y_range)?))
 }
 
 fn simulate_trajectory(mut v: Velocity, bounds: InclusiveBounds) -> Option<i32> {
@@ -48,31 +43,45 @@ fn simulate_trajectory(mut v: Velocity, bounds: InclusiveBounds) -> Option<i32>
     }
 }
 
-pub fn solve(s: &str) -> i32 {
-    let bounds = parse(s);
+pub struct Day17;
 
-    let found = (1..(bounds.0 .1 + 1))
-        .flat_map(|vx| {
-            ((bounds.1 .0)..bounds.1 .0.abs())
-                .filter_map(move |vy| simulate_trajectory((vx, vy), bounds))
-        })
-        .max()
-        .unwrap();
+impl Solution for Day17 {
+    const DAY: u8 = 17;
 
-    found
-}
+    type Input = InclusiveBounds;
+    type Part1 = i32;
+    type Part2 = usize;
 
-pub fn bonus(s: &str) -> usize {
-    let bounds = parse(s);
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
 
-    let num = (1..(bounds.0 .1 + 1))
-        .flat_map(|vx| {
-            ((bounds.1 .0)..bounds.1 .0.abs())
-                .filter_map(move |vy| simulate_trajectory((vx, vy), bounds))
-        })
-        .count();
+    fn part1(&bounds: &Self::Input) -> i32 {
+        (1..(bounds.0 .1 + 1))
+            .flat_map(|vx| {
+                ((bounds.1 .0)..bounds.1 .0.abs())
+                    .filter_map(move |vy| simulate_trajectory((vx, vy), bounds))
+            })
+            .max()
+            .unwrap()
+    }
 
-    num
+    fn part2(&bounds: &Self::Input) -> usize {
+        (1..(bounds.0 .1 + 1))
+            .flat_map(|vx| {
+                ((bounds.1 .0)..bounds.1 .0.abs())
+                    .filter_map(move |vy| simulate_trajectory((vx, vy), bounds))
+            })
+            .count()
+    }
+}
+
+pub fn solve(s: &str) -> i32 {
+    Day17::solve(s)
+}
+
+pub fn bonus(s: &str) -> usize {
+    Day17::bonus(s)
 }
 
 #[test]
@@ -83,3 +92,8 @@ fn test_solve() {
     assert_eq!(solve(s), 45);
     assert_eq!(bonus(s), 112);
 }
+
+#[test]
+fn test_answers() {
+    util::verify::<Day17>();
+}
@@ -6,24 +6,47 @@ mod snum;
 
 use itertools::Itertools;
 use parse::parse_snum;
+use util::{ParseError, Solution};
 
 use crate::snum::Snum;
 
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+
+    type Input = Vec<Snum>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        s.lines()
+            .map(|line| parse_snum(line).map_err(|err| err.within(s, line)))
+            .collect()
+    }
+
+    fn part1(snums: &Self::Input) -> usize {
+        snums.iter().cloned().sum::<Snum>().magnitude()
+    }
+
+    fn part2(snums: &Self::Input) -> usize {
+        snums
+            .iter()
+            .cloned()
+            .tuple_combinations()
+            .flat_map(|(a, b)| [a.clone() + b.clone(), b + a])
+            .map(|n| n.magnitude())
+            .max()
+            .unwrap()
+    }
+}
+
 pub fn solve(s: &str) -> usize {
-    s.lines()
-        .map(|line| parse_snum(line))
-        .sum::<Snum>()
-        .magnitude()
+    Day18::solve(s)
 }
 
 pub fn bonus(s: &str) -> usize {
-    s.lines()
-        .map(|line| parse_snum(line))
-        .tuple_combinations()
-        .flat_map(|(a, b)| [a.clone() + b.clone(), b + a])
-        .map(|n| n.magnitude())
-        .max()
-        .unwrap()
+    Day18::bonus(s)
 }
 
 #[test]
@@ -42,3 +65,8 @@ fn test_solve() {
     assert_eq!(solve(s), 4140);
     assert_eq!(bonus(s), 3993);
 }
+
+#[test]
+fn test_answers() {
+    util::verify::<Day18>();
+}
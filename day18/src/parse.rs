@@ -6,6 +6,7 @@ use nom::{
     sequence::tuple,
     IResult,
 };
+use util::ParseError;
 
 fn p_snum(s: &str) -> IResult<&str, Snum> {
     alt((
@@ -17,8 +18,19 @@ fn p_snum(s: &str) -> IResult<&str, Snum> {
     ))(s)
 }
 
-pub fn parse_snum(s: &str) -> Snum {
-    p_snum(s).unwrap().1
+pub fn parse_snum(s: &str) -> Result<Snum, ParseError> {
+    match p_snum(s) {
+        Ok(("", snum)) => Ok(snum),
+        Ok((rest, _)) => Err(ParseError::at(s, rest, "expected the end of the number")),
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+            Err(ParseError::at(s, err.input, "expected a snailfish number"))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::at(
+            s,
+            &s[s.len()..],
+            "expected a snailfish number",
+        )),
+    }
 }
 
 #[test]
@@ -36,10 +48,15 @@ fn test_parse() {
     );
 
     assert_eq!(
-        parse_snum("[24,[1,2]]-"),
-        Snum::Pair(
+        parse_snum("[24,[1,2]]"),
+        Ok(Snum::Pair(
             Box::new(Snum::Reg(24)),
             Box::new(Snum::Pair(Box::new(Snum::Reg(1)), Box::new(Snum::Reg(2))))
-        )
+        ))
+    );
+    assert_eq!(
+        parse_snum("[24,[1,2]]-").unwrap_err().to_string(),
+        "line 1, column 11: expected the end of the number, found `-`"
     );
+    assert!(parse_snum("[24,[1,").is_err());
 }
@@ -104,10 +104,10 @@ impl Debug for Snum {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Snum::Reg(n) => {
-                write!(f, "{}", n)
+                write!(f, "{n}")
             }
             Snum::Pair(box le, box ri) => {
-                write!(f, "[{},{}]", le, ri)
+                write!(f, "[{le},{ri}]")
             }
         }
     }
@@ -133,7 +133,7 @@ impl Sum for Snum {
             .next()
             .expect("Summation of snailfish numbers is only defined on non-empty lists.");
 
-        while let Some(next) = iter.next() {
+        for next in iter {
             sum = sum + next;
         }
 
@@ -145,71 +145,82 @@ impl Sum for Snum {
 fn test_solve() {
     use crate::parse::parse_snum;
 
-    let snum = parse_snum("[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]");
+    let snum = parse_snum("[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]").unwrap();
 
     assert_eq!(snum.magnitude(), 4140);
 
     assert_eq!(
-        parse_snum("[[[[[9,8],1],2],3],4]").explode_at_depth(0),
-        (parse_snum("[[[[0,9],2],3],4]"), Some((9, 0)))
+        parse_snum("[[[[[9,8],1],2],3],4]")
+            .unwrap()
+            .explode_at_depth(0),
+        (parse_snum("[[[[0,9],2],3],4]").unwrap(), Some((9, 0)))
     );
 
     assert_eq!(
-        parse_snum("[7,[6,[5,[4,[3,2]]]]]").explode_at_depth(0),
-        (parse_snum("[7,[6,[5,[7,0]]]]"), Some((0, 2)))
+        parse_snum("[7,[6,[5,[4,[3,2]]]]]")
+            .unwrap()
+            .explode_at_depth(0),
+        (parse_snum("[7,[6,[5,[7,0]]]]").unwrap(), Some((0, 2)))
     );
 
     assert_eq!(
-        parse_snum("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]").explode_at_depth(0),
+        parse_snum("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]")
+            .unwrap()
+            .explode_at_depth(0),
         (
-            parse_snum("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]"),
+            parse_snum("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]").unwrap(),
             Some((0, 0)),
         ),
     );
 
     assert_eq!(
-        parse_snum("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]").explode_at_depth(0),
-        (parse_snum("[[3,[2,[8,0]]],[9,[5,[7,0]]]]"), Some((0, 2)))
+        parse_snum("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]")
+            .unwrap()
+            .explode_at_depth(0),
+        (
+            parse_snum("[[3,[2,[8,0]]],[9,[5,[7,0]]]]").unwrap(),
+            Some((0, 2))
+        )
     );
 
     assert_eq!(
-        parse_snum("[[[[4,3],4],4],[7,[[8,4],9]]]") + parse_snum("[1,1]"),
-        parse_snum("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]")
+        parse_snum("[[[[4,3],4],4],[7,[[8,4],9]]]").unwrap() + parse_snum("[1,1]").unwrap(),
+        parse_snum("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").unwrap()
     );
 
     assert_eq!(
-        parse_snum("[[[[1,1],[2,2]],[3,3]],[4,4]]") + parse_snum("[5,5]"),
-        parse_snum("[[[[3,0],[5,3]],[4,4]],[5,5]]")
+        parse_snum("[[[[1,1],[2,2]],[3,3]],[4,4]]").unwrap() + parse_snum("[5,5]").unwrap(),
+        parse_snum("[[[[3,0],[5,3]],[4,4]],[5,5]]").unwrap()
     );
 
     assert_eq!(
-        parse_snum("[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]")
-            + parse_snum("[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]"),
-        parse_snum("[[[[7,8],[6,6]],[[6,0],[7,7]]],[[[7,8],[8,8]],[[7,9],[0,6]]]]")
+        parse_snum("[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]").unwrap()
+            + parse_snum("[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]").unwrap(),
+        parse_snum("[[[[7,8],[6,6]],[[6,0],[7,7]]],[[[7,8],[8,8]],[[7,9],[0,6]]]]").unwrap()
     );
 
     assert_eq!(
         vec![
-            parse_snum("[1,1]"),
-            parse_snum("[2,2]"),
-            parse_snum("[3,3]"),
-            parse_snum("[4,4]"),
+            parse_snum("[1,1]").unwrap(),
+            parse_snum("[2,2]").unwrap(),
+            parse_snum("[3,3]").unwrap(),
+            parse_snum("[4,4]").unwrap(),
         ]
         .into_iter()
         .sum::<Snum>(),
-        parse_snum("[[[[1,1],[2,2]],[3,3]],[4,4]]")
+        parse_snum("[[[[1,1],[2,2]],[3,3]],[4,4]]").unwrap()
     );
 
     assert_eq!(
         vec![
-            parse_snum("[1,1]"),
-            parse_snum("[2,2]"),
-            parse_snum("[3,3]"),
-            parse_snum("[4,4]"),
-            parse_snum("[5,5]"),
+            parse_snum("[1,1]").unwrap(),
+            parse_snum("[2,2]").unwrap(),
+            parse_snum("[3,3]").unwrap(),
+            parse_snum("[4,4]").unwrap(),
+            parse_snum("[5,5]").unwrap(),
         ]
         .into_iter()
         .sum::<Snum>(),
-        parse_snum("[[[[3,0],[5,3]],[4,4]],[5,5]]")
+        parse_snum("[[[[3,0],[5,3]],[4,4]],[5,5]]").unwrap()
     );
 }
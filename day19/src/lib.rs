@@ -3,6 +3,7 @@
 use itertools::Itertools;
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
+use util::{parse_num, ParseError, Solution};
 
 type Pos = (i32, i32, i32);
 type Dist = (i32, i32, i32);
@@ -10,21 +11,38 @@ type Scan = HashSet<Pos>;
 
 const DEBUG: bool = false;
 
-fn parse(s: &str) -> Vec<Scan> {
-    s.split("\n\n")
-        .map(|s| {
-            s.lines()
-                .skip(1)
+fn parse(s: &str) -> Result<Vec<Scan>, ParseError> {
+    s.trim_end()
+        .split("\n\n")
+        .map(|scan| {
+            let mut lines = scan.lines();
+
+            match lines.next() {
+                Some(header) if header.starts_with("--- scanner ") => {}
+                header => {
+                    return Err(ParseError::at(
+                        s,
+                        header.unwrap_or(scan),
+                        "expected a `--- scanner N ---` header",
+                    ))
+                }
+            }
+
+            lines
                 .map(|line| {
                     let ns = line
-                        .split(",")
-                        .map(|n| n.parse::<i32>().unwrap())
-                        .collect::<Vec<i32>>();
-                    (ns[0], ns[1], ns[2])
+                        .split(',')
+                        .map(|n| parse_num(s, n))
+                        .collect::<Result<Vec<i32>, _>>()?;
+
+                    match ns[..] {
+                        [x, y, z] => Ok((x, y, z)),
+                        _ => Err(ParseError::at(s, line, "expected `x,y,z`")),
+                    }
                 })
-                .collect::<HashSet<_>>()
+                .collect::<Result<HashSet<_>, _>>()
         })
-        .collect::<Vec<_>>()
+        .collect()
 }
 
 type Orient = fn(Pos) -> Pos;
@@ -98,19 +116,25 @@ fn find_overlap(sa: &Scan, sb: &Scan) -> Option<(Scan, Pos)> {
     None
 }
 
-pub fn solve_both_parts(s: &str) -> (usize, usize) {
-    let mut scans = parse(s).into_iter().enumerate();
+/// All scans merged into scanner 0's frame of reference.
+pub struct Map {
+    beacons: Scan,
+    sensors: Vec<Pos>,
+}
+
+fn assemble(scans: Vec<Scan>) -> Map {
+    let mut scans = scans.into_iter().enumerate();
     let first = scans.next().unwrap();
 
     let mut done = vec![first];
     let mut sensors = vec![(0, 0, 0)];
     let mut todo = scans.collect::<Vec<_>>();
 
-    while todo.len() > 0 {
+    while !todo.is_empty() {
         todo.drain_filter(|(j, sb)| {
             match done
                 .par_iter()
-                .find_map_any(|(i, sa)| find_overlap(sa, &sb).map(|sat| (i, sat)))
+                .find_map_any(|(i, sa)| find_overlap(sa, sb).map(|sat| (i, sat)))
             {
                 None => false,
                 Some((i, (transformed_back, s0_back))) => {
@@ -127,21 +151,55 @@ pub fn solve_both_parts(s: &str) -> (usize, usize) {
         });
     }
 
-    let num_beacons = done
+    let beacons = done
         .into_iter()
         .map(|p| p.1)
         .reduce(|sa, sb| sa.union(&sb).cloned().collect::<Scan>())
-        .unwrap()
-        .len();
+        .unwrap();
 
-    let max_dist = sensors
-        .into_iter()
+    Map { beacons, sensors }
+}
+
+fn largest_distance(map: &Map) -> usize {
+    map.sensors
+        .iter()
+        .cloned()
         .tuple_combinations()
         .map(manhattan)
         .max()
-        .unwrap();
+        .unwrap()
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u8 = 19;
+
+    type Input = Vec<Scan>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    /// Aligning the scans is the expensive bit, and both parts do it, so that it's timed as
+    /// solving rather than parsing.
+    fn part1(scans: &Self::Input) -> usize {
+        assemble(scans.clone()).beacons.len()
+    }
 
-    (num_beacons, max_dist)
+    fn part2(scans: &Self::Input) -> usize {
+        largest_distance(&assemble(scans.clone()))
+    }
+}
+
+/// Both parts from a single alignment.
+pub fn solve_both_parts(s: &str) -> (usize, usize) {
+    let scans = Day19::parse(s).unwrap_or_else(|err| panic!("{err}"));
+    let map = assemble(scans);
+
+    (map.beacons.len(), largest_distance(&map))
 }
 
 pub fn solve(s: &str) -> usize {
@@ -295,3 +353,8 @@ fn test_solve() {
     assert_eq!(solve(s), 79);
     assert_eq!(bonus(s), 3621);
 }
+
+#[test]
+fn test_answers() {
+    util::verify::<Day19>();
+}
@@ -1,226 +1,134 @@
-use std::ops::{Index, IndexMut};
-
-type Algo = Vec<usize>;
-type Pos = (usize, usize);
+use std::fmt::{self, Display};
+use util::{grid::Grid, split_once, ParseError, Solution};
+
+pub type Algo = Vec<usize>;
+
+/// The 3x3 square around a pixel, read as a 9-bit number from the top left
+const SQUARE: [(isize, isize); 9] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (0, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
 
 #[derive(Debug, PartialEq, Clone)]
-struct Image {
-    width: usize,
-    height: usize,
-    pixels: Vec<Vec<usize>>,
-}
+pub struct Image(Grid<usize>);
 
 impl Image {
-    fn from_pixels(pixels: Vec<Vec<usize>>) -> Self {
-        Image {
-            height: pixels.len(),
-            width: pixels[0].len(),
-            pixels,
-        }
-    }
-
-    fn map(&self, f: impl Fn(usize, (usize, usize)) -> usize) -> Self {
-        Self {
-            width: self.width,
-            height: self.height,
-            pixels: self
-                .pixels
-                .iter()
-                .enumerate()
-                .map(|(y, row)| row.iter().enumerate().map(|(x, &b)| f(b, (x, y))).collect())
-                .collect::<Vec<_>>(),
-        }
+    fn contract(&self, amount: usize) -> Self {
+        let Image(pixels) = self;
+
+        Image(Grid::new(
+            pixels.width() - 2 * amount,
+            pixels.height() - 2 * amount,
+            |(x, y)| pixels[(x + amount, y + amount)],
+        ))
     }
 
-    fn viz(&self) {
-        println!(
-            "{}",
-            self.pixels
-                .iter()
-                .map(|row| {
-                    row.iter()
-                        .map(|&b| if b == 1 { '#' } else { '.' })
-                        .collect::<String>()
-                })
-                .collect::<Vec<_>>()
-                .join("\n")
-        );
+    fn expand(&self, amount: usize) -> Self {
+        let Image(pixels) = self;
+
+        Image(Grid::new(
+            pixels.width() + 2 * amount,
+            pixels.height() + 2 * amount,
+            |(x, y)| {
+                pixels
+                    .offset((x, y), (-(amount as isize), -(amount as isize)))
+                    .map_or(0, |p| pixels[p])
+            },
+        ))
     }
 
-    fn contract(&mut self, amount: usize) {
-        for row in &mut self.pixels {
-            for _ in 0..amount {
-                row.remove(0);
-                row.remove(row.len() - 1);
-            }
-        }
+    fn enhance(&self, algo: &Algo) -> Image {
+        let Image(pixels) = self;
 
-        for _ in 0..amount {
-            self.pixels.remove(0);
-            self.pixels.remove(self.pixels.len() - 1);
-        }
+        Image(Grid::new(pixels.width(), pixels.height(), |p| {
+            let num = SQUARE
+                .iter()
+                .map(|&d| pixels.offset(p, d).map_or(0, |n| pixels[n]))
+                .fold(0, |num, b| (num << 1) | b);
 
-        self.width -= 2 * amount;
-        self.height -= 2 * amount;
+            algo[num]
+        }))
     }
 
-    fn expand(&mut self, amount: usize) {
-        for _ in 0..amount {
-            self.pixels.insert(0, vec![0; self.width]);
-            self.pixels.push(vec![0; self.width]);
-        }
+    fn checksum(&self) -> usize {
+        self.0.values().sum()
+    }
+}
 
-        self.pixels = self
-            .pixels
-            .clone()
-            .into_iter()
-            .map(|mut row| {
-                row.splice(0..0, vec![0; amount]);
-                row.append(&mut vec![0; amount]);
-                row
-            })
-            .collect();
-
-        self.width += 2 * amount;
-        self.height += 2 * amount;
+impl Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.render(|&b| if b == 1 { '#' } else { '.' }))
     }
+}
 
-    fn neighbors(&self, x: usize, y: usize) -> Vec<(usize, (usize, usize))> {
-        let mut neighbors = vec![];
+pub struct Day20;
 
-        if y > 0 {
-            if x > 0 {
-                neighbors.push((256, (x - 1, y - 1)));
-            }
-            neighbors.push((128, (x, y - 1)));
-            if x < self.width - 1 {
-                neighbors.push((64, (x + 1, y - 1)));
-            }
-        }
+impl Solution for Day20 {
+    const DAY: u8 = 20;
 
-        if x > 0 {
-            neighbors.push((32, (x - 1, y)));
-        }
-        neighbors.push((16, (x, y)));
-        if x < self.width - 1 {
-            neighbors.push((8, (x + 1, y)));
-        }
+    type Input = (Algo, Image);
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let (algo, im) = split_once(s, s, "\n\n")?;
+        let pixel = |c| match c {
+            '#' => Some(1),
+            '.' => Some(0),
+            _ => None,
+        };
 
-        if y < self.height - 1 {
-            if x > 0 {
-                neighbors.push((4, (x - 1, y + 1)));
+        let algo = algo.trim();
+        let algo = match algo.char_indices().find(|&(_, c)| pixel(c).is_none()) {
+            Some((i, c)) => {
+                let bad = &algo[i..i + c.len_utf8()];
+                return Err(ParseError::at(s, bad, "expected `#` or `.`"));
             }
-            neighbors.push((2, (x, y + 1)));
-            if x < self.width - 1 {
-                neighbors.push((1, (x + 1, y + 1)));
+            None if algo.len() != 512 => {
+                return Err(ParseError::at(s, algo, "expected 512 pixels of algorithm"));
             }
-        }
+            None => algo.chars().filter_map(pixel).collect::<Algo>(),
+        };
 
-        neighbors
-    }
+        let im = im.trim();
+        let image = Image(Grid::parse_with(im, pixel).map_err(|err| err.within(s, im))?);
 
-    fn enhance(&self, algo: &Algo) -> Image {
-        self.map(|_, (x, y)| {
-            let num = self
-                .neighbors(x, y)
-                .into_iter()
-                .map(|(k, n)| k * self[n])
-                .sum::<usize>();
-
-            algo[num]
-        })
+        Ok((algo, image))
     }
 
-    fn checksum(&self) -> usize {
-        self.pixels
-            .iter()
-            .map(|row| row.iter().sum::<usize>())
-            .sum()
+    fn part1((algo, image): &Self::Input) -> usize {
+        image
+            .expand(10)
+            .enhance(algo)
+            .enhance(algo)
+            .contract(8)
+            .checksum()
     }
-}
 
-impl Index<Pos> for Image {
-    type Output = usize;
+    fn part2((algo, image): &Self::Input) -> usize {
+        let mut image = image.clone();
 
-    fn index(&self, (x, y): Pos) -> &Self::Output {
-        &self.pixels[y][x]
-    }
-}
+        for _ in 0..25 {
+            image = image.expand(10).enhance(algo).enhance(algo).contract(8);
+        }
 
-impl IndexMut<Pos> for Image {
-    fn index_mut(&mut self, (x, y): Pos) -> &mut Self::Output {
-        &mut self.pixels[y][x]
+        image.checksum()
     }
 }
 
 pub fn solve(s: &str) -> usize {
-    let (algo, im) = s.split_once("\n\n").unwrap();
-
-    let algo = algo
-        .trim()
-        .chars()
-        .map(|c| if c == '#' { 1 } else { 0 })
-        .collect::<Algo>();
-
-    let mut image = Image::from_pixels(
-        im.trim()
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| if c == '#' { 1 } else { 0 })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>(),
-    );
-
-    println!();
-    println!("Initial");
-    image.viz();
-
-    image.expand(10);
-
-    println!();
-    println!("First pass");
-    image.enhance(&algo);
-    image.viz();
-
-    println!();
-    println!("Second pass");
-    image.enhance(&algo);
-    image.contract(8);
-    image.viz();
-
-    image.checksum()
+    Day20::solve(s)
 }
 
 pub fn bonus(s: &str) -> usize {
-    let (algo, im) = s.split_once("\n\n").unwrap();
-
-    let algo = algo
-        .trim()
-        .chars()
-        .map(|c| if c == '#' { 1 } else { 0 })
-        .collect::<Algo>();
-
-    let mut image = Image::from_pixels(
-        im.trim()
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| if c == '#' { 1 } else { 0 })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>(),
-    );
-
-    for _ in 0..25 {
-        image.expand(10);
-        image = image.enhance(&algo);
-        image = image.enhance(&algo);
-        image.contract(8);
-    }
-
-    image.checksum()
+    Day20::bonus(s)
 }
 
 #[test]
@@ -237,3 +145,8 @@ fn test_solve() {
     assert_eq!(solve(s), 35);
     assert_eq!(bonus(s), 3351);
 }
+
+#[test]
+fn test_answers() {
+    util::verify::<Day20>();
+}
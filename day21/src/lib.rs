@@ -2,12 +2,10 @@ use std::{
     iter::Sum,
     ops::{Add, Mul},
 };
+use util::{parse_num, split_once, Memo, ParseError, Solution};
 
-pub fn solve(s: &str) -> usize {
-    let mut pos = s
-        .lines()
-        .map(|line| line.split_once(": ").unwrap().1.parse::<usize>().unwrap() - 1usize)
-        .collect::<Vec<_>>();
+fn practice(start: [u64; 2]) -> usize {
+    let mut pos = start.map(|p| p as usize - 1);
 
     let mut score = vec![0, 0];
 
@@ -69,47 +67,98 @@ impl Sum for NumberOfSteps {
     }
 }
 
-fn numsteps_both(turn: usize, pos: [u64; 2], score: [u64; 2]) -> [NumberOfSteps; 2] {
+/// Whose turn it is, the positions and the scores
+type Game = (usize, [u64; 2], [u64; 2]);
+
+fn numsteps_both(
+    memo: &Memo<Game, [NumberOfSteps; 2]>,
+    turn: usize,
+    pos: [u64; 2],
+    score: [u64; 2],
+) -> [NumberOfSteps; 2] {
     if score[0] >= 21 {
         return [NumberOfSteps(vec![0, 1]), NumberOfSteps::zero()];
     } else if score[1] >= 21 {
         return [NumberOfSteps::zero(), NumberOfSteps(vec![0, 1])];
     }
 
-    DICE_ROLLS
-        .iter()
-        .map(|&(steps, num_universes)| {
-            let target_pos = (pos[turn] + steps - 1) % 10 + 1;
+    memo.get_or_insert_with((turn, pos, score), || {
+        DICE_ROLLS
+            .iter()
+            .map(|&(steps, num_universes)| {
+                let target_pos = (pos[turn] + steps - 1) % 10 + 1;
 
-            let mut new_pos = pos;
-            new_pos[turn] = target_pos;
+                let mut new_pos = pos;
+                new_pos[turn] = target_pos;
 
-            let mut new_score = score;
-            new_score[turn] += target_pos;
+                let mut new_score = score;
+                new_score[turn] += target_pos;
 
-            let steps = numsteps_both(1 - turn, new_pos, new_score);
+                let steps = numsteps_both(memo, 1 - turn, new_pos, new_score);
 
-            steps.map(|s| s.shift() * num_universes)
-        })
-        .fold(
-            [NumberOfSteps::zero(), NumberOfSteps::zero()],
-            |[accum0, accum1], [n0, n1]| [accum0 + n0, accum1 + n1],
-        )
+                steps.map(|s| s.shift() * num_universes)
+            })
+            .fold(
+                [NumberOfSteps::zero(), NumberOfSteps::zero()],
+                |[accum0, accum1], [n0, n1]| [accum0 + n0, accum1 + n1],
+            )
+    })
 }
 
-pub fn bonus(s: &str) -> u64 {
-    let pos = s
-        .lines()
-        .map(|line| line.split_once(": ").unwrap().1.parse::<u64>().unwrap())
-        .collect::<Vec<_>>();
-
-    let res = numsteps_both(0, [pos[0], pos[1]], [0, 0]);
+fn dirac(start: [u64; 2]) -> u64 {
+    let res = numsteps_both(&Memo::new(), 0, start, [0, 0]);
 
     let sums = res.map(|ns| ns.0.iter().sum::<u64>());
 
     sums[0].max(sums[1])
 }
 
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+
+    /// Starting positions, 1-based
+    type Input = [u64; 2];
+    type Part1 = usize;
+    type Part2 = u64;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let pos = s
+            .lines()
+            .map(|line| {
+                let (_, pos) = split_once(s, line, ": ")?;
+
+                match parse_num(s, pos)? {
+                    pos @ 1..=10 => Ok(pos),
+                    _ => Err(ParseError::at(s, pos, "expected a position from 1 to 10")),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match pos[..] {
+            [a, b] => Ok([a, b]),
+            _ => Err(ParseError::at(s, s, "expected two players")),
+        }
+    }
+
+    fn part1(&start: &Self::Input) -> usize {
+        practice(start)
+    }
+
+    fn part2(&start: &Self::Input) -> u64 {
+        dirac(start)
+    }
+}
+
+pub fn solve(s: &str) -> usize {
+    Day21::solve(s)
+}
+
+pub fn bonus(s: &str) -> u64 {
+    Day21::bonus(s)
+}
+
 #[test]
 fn test_solve() {
     let s = "Player 1 starting position: 4
@@ -119,3 +168,8 @@ Player 2 starting position: 8
     assert_eq!(solve(s), 739785);
     assert_eq!(bonus(s), 444356092776315);
 }
+
+#[test]
+fn test_answers() {
+    util::verify::<Day21>();
+}
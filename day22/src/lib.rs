@@ -1,22 +1,13 @@
 #![feature(drain_filter)]
 
 use std::{collections::HashSet, fmt::Debug, hash::Hash};
+use util::{parse_num, split_once, ParseError, Solution};
 
-pub fn solve(s: &str) -> usize {
+fn initialize(steps: &[(bool, Cuboid)]) -> usize {
     let mut grid = HashSet::new();
 
-    for line in s.lines() {
-        let should_turn_on = line.starts_with("on");
-        let cuboid = line
-            .split(",")
-            .map(|s| s.split_once("=").unwrap().1.split_once("..").unwrap())
-            .map(|(min, max)| {
-                (
-                    min.parse::<i32>().unwrap().max(-50),
-                    max.parse::<i32>().unwrap().min(50),
-                )
-            })
-            .collect::<Vec<_>>();
+    for &(should_turn_on, Cuboid(spans)) in steps {
+        let cuboid = spans.map(|Span { min, max }| (min.max(-50), max.min(50)));
 
         for x in cuboid[0].0..=cuboid[0].1 {
             for y in cuboid[1].0..=cuboid[1].1 {
@@ -36,7 +27,7 @@ pub fn solve(s: &str) -> usize {
 
 /// Inclusive
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct Span {
+pub struct Span {
     min: i32,
     max: i32,
 }
@@ -57,10 +48,9 @@ impl Span {
     fn subtract(&self, b: Span) -> Option<(HashSet<Span>, Span)> {
         let a = *self;
 
-        if b.max < a.min {
+        if b.max < a.min || a.max < b.min {
             // BBB  AAA
-            None
-        } else if a.max < b.min {
+            // or
             // AAA  BBB
             None
         } else if b.min <= a.min && a.max <= b.max {
@@ -95,7 +85,7 @@ impl Span {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct Cuboid([Span; 3]);
+pub struct Cuboid([Span; 3]);
 
 impl Debug for Cuboid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -186,23 +176,10 @@ impl CuboidSet {
     }
 }
 
-pub fn bonus(s: &str) -> u64 {
+fn reboot(steps: &[(bool, Cuboid)]) -> u64 {
     let mut set = CuboidSet::new();
 
-    for line in s.lines() {
-        let should_turn_on = line.starts_with("on");
-        let cuboid = Cuboid(
-            line.split(",")
-                .map(|s| s.split_once("=").unwrap().1.split_once("..").unwrap())
-                .map(|(min, max)| Span {
-                    min: min.parse::<i32>().unwrap(),
-                    max: max.parse::<i32>().unwrap(),
-                })
-                .collect::<Vec<_>>()
-                .try_into()
-                .unwrap(),
-        );
-
+    for &(should_turn_on, cuboid) in steps {
         if should_turn_on {
             set.add(cuboid);
         } else {
@@ -213,6 +190,66 @@ pub fn bonus(s: &str) -> u64 {
     set.area()
 }
 
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u8 = 22;
+
+    type Input = Vec<(bool, Cuboid)>;
+    type Part1 = usize;
+    type Part2 = u64;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        s.lines()
+            .map(|line| {
+                let (state, ranges) = split_once(s, line, " ")?;
+
+                let should_turn_on = match state {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(ParseError::at(s, state, "expected on or off")),
+                };
+
+                let spans = ranges
+                    .split(',')
+                    .map(|range| {
+                        let (_, range) = split_once(s, range, "=")?;
+                        let (min, max) = split_once(s, range, "..")?;
+                        Ok(Span {
+                            min: parse_num(s, min)?,
+                            max: parse_num(s, max)?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let cuboid = Cuboid(
+                    spans
+                        .try_into()
+                        .map_err(|_| ParseError::at(s, ranges, "expected x, y and z ranges"))?,
+                );
+
+                Ok((should_turn_on, cuboid))
+            })
+            .collect()
+    }
+
+    fn part1(steps: &Self::Input) -> usize {
+        initialize(steps)
+    }
+
+    fn part2(steps: &Self::Input) -> u64 {
+        reboot(steps)
+    }
+}
+
+pub fn solve(s: &str) -> usize {
+    Day22::solve(s)
+}
+
+pub fn bonus(s: &str) -> u64 {
+    Day22::bonus(s)
+}
+
 #[test]
 fn test_solve() {
     let s = "on x=10..12,y=10..12,z=10..12
@@ -313,3 +350,8 @@ off x=-93533..-4276,y=-16170..68771,z=-104985..-24507
 
     assert_eq!(bonus(s), 2758514936282235);
 }
+
+#[test]
+fn test_answers() {
+    util::verify::<Day22>();
+}
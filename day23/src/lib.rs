@@ -0,0 +1,347 @@
+use std::fmt::{self, Display};
+use util::{
+    search::{astar, Graph},
+    ParseError, Solution,
+};
+
+const EMPTY: u8 = b'.';
+const ENERGY: [usize; 4] = [1, 10, 100, 1000];
+/// Hallway spots right outside each room, where nobody may stop.
+const ENTRANCES: [usize; 4] = [2, 4, 6, 8];
+
+/// Room an amphipod (`b'A'..=b'D'`) belongs in.
+fn home(amphipod: u8) -> usize {
+    (amphipod - b'A') as usize
+}
+
+/// Every cell holds an amphipod's letter, or `EMPTY`. Rooms are listed top to bottom.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Burrow<const DEPTH: usize> {
+    hallway: [u8; 11],
+    rooms: [[u8; DEPTH]; 4],
+}
+
+impl<const DEPTH: usize> Burrow<DEPTH> {
+    pub fn is_organized(&self) -> bool {
+        (0..4).all(|r| self.rooms[r].iter().all(|&a| a != EMPTY && home(a) == r))
+    }
+
+    /// Room `r` holds nobody who still has to leave it.
+    fn is_settled(&self, r: usize) -> bool {
+        self.rooms[r].iter().all(|&a| a == EMPTY || home(a) == r)
+    }
+
+    /// Nobody in the hallway between `from` (exclusive) and `to` (inclusive).
+    fn is_clear(&self, from: usize, to: usize) -> bool {
+        let between = if from < to {
+            &self.hallway[from + 1..=to]
+        } else {
+            &self.hallway[to..from]
+        };
+
+        between.iter().all(|&a| a == EMPTY)
+    }
+
+    /// Every state one move away, with the energy that move takes. Amphipods either step out of
+    /// a room into the hallway, or from the hallway all the way into their own room.
+    fn moves(&self) -> Vec<(Self, usize)> {
+        // going home can never make things worse, so if anyone can, that's the only move worth
+        // looking at
+        for (h, &a) in self.hallway.iter().enumerate() {
+            if a == EMPTY {
+                continue;
+            }
+
+            let r = home(a);
+            if !self.is_settled(r) || !self.is_clear(h, ENTRANCES[r]) {
+                continue;
+            }
+
+            if let Some(depth) = self.rooms[r].iter().rposition(|&b| b == EMPTY) {
+                let mut next = self.clone();
+                next.hallway[h] = EMPTY;
+                next.rooms[r][depth] = a;
+                let steps = h.abs_diff(ENTRANCES[r]) + depth + 1;
+                return vec![(next, steps * ENERGY[r])];
+            }
+        }
+
+        let mut moves = vec![];
+
+        for r in (0..4).filter(|&r| !self.is_settled(r)) {
+            let Some(depth) = self.rooms[r].iter().position(|&a| a != EMPTY) else {
+                continue;
+            };
+            let a = self.rooms[r][depth];
+
+            for h in (0..11).filter(|h| !ENTRANCES.contains(h)) {
+                if !self.is_clear(ENTRANCES[r], h) {
+                    continue;
+                }
+
+                let mut next = self.clone();
+                next.rooms[r][depth] = EMPTY;
+                next.hallway[h] = a;
+                let steps = depth + 1 + h.abs_diff(ENTRANCES[r]);
+                moves.push((next, steps * ENERGY[home(a)]));
+            }
+        }
+
+        moves
+    }
+
+    /// Energy it would take if nobody were ever in anyone else's way, which never overestimates.
+    fn min_energy_left(&self) -> usize {
+        let hallway = self
+            .hallway
+            .iter()
+            .enumerate()
+            .filter(|&(_, &a)| a != EMPTY)
+            .map(|(h, &a)| (h.abs_diff(ENTRANCES[home(a)]) + 1) * ENERGY[home(a)]);
+
+        let rooms = (0..4).flat_map(|r| {
+            let room = &self.rooms[r];
+            (0..DEPTH)
+                .filter(move |&depth| room[depth] != EMPTY)
+                .map(move |depth| {
+                    let a = room[depth];
+                    let steps = if home(a) != r {
+                        depth + 1 + ENTRANCES[r].abs_diff(ENTRANCES[home(a)]) + 1
+                    } else if room[depth..].iter().all(|&b| home(b) == r) {
+                        0
+                    } else {
+                        // has to let someone below out: up, aside, back and down again
+                        depth + 1 + 2 + 1
+                    };
+                    steps * ENERGY[home(a)]
+                })
+        });
+
+        hallway.chain(rooms).sum()
+    }
+
+    /// Parses a burrow diagram with rooms `DEPTH` deep.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let cell = |line: &str, col: usize| match line.get(col..col + 1) {
+            Some(c @ ("." | "A" | "B" | "C" | "D")) => Ok(c.as_bytes()[0]),
+            Some(c) => Err(ParseError::at(s, c, "expected an amphipod or `.`")),
+            None => Err(ParseError::at(
+                s,
+                &line[line.len()..],
+                "expected an amphipod or `.`",
+            )),
+        };
+
+        let mut lines = s.lines().skip(1);
+        let mut row = || {
+            lines
+                .next()
+                .ok_or_else(|| ParseError::at(s, &s[s.len()..], "expected a row of the burrow"))
+        };
+
+        let mut burrow = Burrow {
+            hallway: [EMPTY; 11],
+            rooms: [[EMPTY; DEPTH]; 4],
+        };
+
+        let first = row()?;
+        for (h, spot) in burrow.hallway.iter_mut().enumerate() {
+            *spot = cell(first, h + 1)?;
+        }
+
+        let mut last = first;
+        for depth in 0..DEPTH {
+            last = row()?;
+            for (r, &entrance) in ENTRANCES.iter().enumerate() {
+                burrow.rooms[r][depth] = cell(last, entrance + 1)?;
+            }
+        }
+
+        for a in b'A'..=b'D' {
+            let cells = burrow.hallway.iter().chain(burrow.rooms.iter().flatten());
+            let count = cells.filter(|&&b| b == a).count();
+            if count != DEPTH {
+                // from the hallway down to the bottom of the rooms
+                let start = first.as_ptr() as usize - s.as_ptr() as usize;
+                let end = last.as_ptr() as usize - s.as_ptr() as usize + last.len();
+
+                return Err(ParseError::at(
+                    s,
+                    &s[start..end],
+                    format!(
+                        "expected {DEPTH} amphipods of each type, not {count} {}s",
+                        a as char
+                    ),
+                ));
+            }
+        }
+
+        Ok(burrow)
+    }
+
+    /// Cheapest way to get everyone home, as the energy it takes and every state along the way.
+    pub fn organize(&self) -> Option<(usize, Vec<Self>)> {
+        astar(
+            &Moves::<DEPTH>,
+            self.clone(),
+            Self::is_organized,
+            Self::min_energy_left,
+        )
+    }
+}
+
+impl Burrow<2> {
+    /// The full diagram, with the two folded-up rows in the middle of every room.
+    pub fn unfold(&self) -> Burrow<4> {
+        let mut rooms = [[EMPTY; 4]; 4];
+        for (r, hidden) in [*b"DD", *b"CB", *b"BA", *b"AC"].into_iter().enumerate() {
+            rooms[r] = [self.rooms[r][0], hidden[0], hidden[1], self.rooms[r][1]];
+        }
+
+        Burrow {
+            hallway: self.hallway,
+            rooms,
+        }
+    }
+}
+
+impl<const DEPTH: usize> Display for Burrow<DEPTH> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "#############")?;
+        writeln!(f, "#{}#", String::from_utf8_lossy(&self.hallway))?;
+
+        for depth in 0..DEPTH {
+            let [a, b, c, d] = self.rooms.map(|room| room[depth] as char);
+            let (left, right) = if depth == 0 { ("##", "##") } else { ("  ", "") };
+            writeln!(f, "{left}#{a}#{b}#{c}#{d}#{right}")?;
+        }
+
+        write!(f, "  #########")
+    }
+}
+
+/// Every burrow state, and the moves between them.
+struct Moves<const DEPTH: usize>;
+
+impl<const DEPTH: usize> Graph for Moves<DEPTH> {
+    type Node = Burrow<DEPTH>;
+    type Cost = usize;
+
+    fn neighbors(&self, burrow: &Burrow<DEPTH>) -> Vec<(Burrow<DEPTH>, usize)> {
+        burrow.moves()
+    }
+}
+
+/// The least energy it takes to organize the burrow, if it can be done at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Energy(usize),
+    Unsolvable,
+}
+
+impl<const DEPTH: usize> From<&Burrow<DEPTH>> for Outcome {
+    fn from(burrow: &Burrow<DEPTH>) -> Self {
+        burrow
+            .organize()
+            .map_or(Outcome::Unsolvable, |(energy, _)| Outcome::Energy(energy))
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Energy(energy) => write!(f, "{energy}"),
+            Outcome::Unsolvable => write!(f, "unsolvable"),
+        }
+    }
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u8 = 23;
+
+    type Input = Burrow<2>;
+    type Part1 = Outcome;
+    type Part2 = Outcome;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        Burrow::parse(s)
+    }
+
+    fn part1(burrow: &Self::Input) -> Outcome {
+        burrow.into()
+    }
+
+    fn part2(burrow: &Self::Input) -> Outcome {
+        (&burrow.unfold()).into()
+    }
+}
+
+pub fn solve(s: &str) -> Outcome {
+    Day23::solve(s)
+}
+
+pub fn bonus(s: &str) -> Outcome {
+    Day23::bonus(s)
+}
+
+#[test]
+fn test_solve() {
+    let s = "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########";
+
+    assert_eq!(solve(s), Outcome::Energy(12521));
+    assert_eq!(bonus(s), Outcome::Energy(44169));
+}
+
+#[test]
+fn test_organize() {
+    let s = "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########";
+
+    let burrow = Burrow::<2>::parse(s).unwrap();
+    assert_eq!(burrow.to_string(), s);
+
+    let (energy, path) = burrow.organize().unwrap();
+    assert_eq!(energy, 12521);
+    assert_eq!(path.first(), Some(&burrow));
+    assert!(path.last().unwrap().is_organized());
+
+    let unfolded = Burrow::<4>::parse(&burrow.unfold().to_string()).unwrap();
+    assert_eq!(unfolded.rooms[0], *b"BDDA");
+
+    assert!(Burrow::<2>::parse("#############\n#...........#\n###B#C#B#D###").is_err());
+    assert_eq!(
+        Burrow::<2>::parse(s.replace('D', "E").as_str())
+            .unwrap_err()
+            .to_string(),
+        "line 3, column 10: expected an amphipod or `.`, found `E`"
+    );
+    assert_eq!(
+        Burrow::<2>::parse(s.replace("#A#D", "#A#A").as_str())
+            .unwrap_err()
+            .to_string(),
+        "line 2, column 1: expected 2 amphipods of each type, not 3 As, found `#...........#`"
+    );
+
+    // the D and the A in the hallway are each in the other's way home
+    let stuck = "#############
+#...D.A.....#
+###.#B#C#.###
+  #A#B#C#D#
+  #########";
+    assert_eq!(solve(stuck), Outcome::Unsolvable);
+    assert_eq!(solve(stuck).to_string(), "unsolvable");
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day23>();
+}
@@ -0,0 +1,6 @@
+use day23::*;
+use util::*;
+
+fn main() {
+    run_day::<Day23>();
+}
@@ -0,0 +1,168 @@
+use std::fmt::{self, Display};
+use util::{parse_num, ParseError};
+
+/// Index of the z register, which MONAD leaves at 0 for a valid model number.
+pub const Z: usize = 3;
+const REGISTERS: [&str; 4] = ["w", "x", "y", "z"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Reg(usize),
+    Num(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Mul,
+    Div,
+    Mod,
+    Eql,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instr {
+    Inp(usize),
+    /// `a = a op b`
+    Op(Op, usize, Operand),
+}
+
+impl Display for Instr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Instr::Inp(a) => write!(f, "inp {}", REGISTERS[a]),
+            Instr::Op(op, a, b) => {
+                let op = match op {
+                    Op::Add => "add",
+                    Op::Mul => "mul",
+                    Op::Div => "div",
+                    Op::Mod => "mod",
+                    Op::Eql => "eql",
+                };
+                write!(f, "{op} {}", REGISTERS[a])?;
+
+                match b {
+                    Operand::Reg(b) => write!(f, " {}", REGISTERS[b]),
+                    Operand::Num(n) => write!(f, " {n}"),
+                }
+            }
+        }
+    }
+}
+
+fn register(s: &str, word: &str) -> Result<usize, ParseError> {
+    REGISTERS
+        .iter()
+        .position(|&r| r == word)
+        .ok_or_else(|| ParseError::at(s, word, "expected a register"))
+}
+
+/// One instruction per line.
+pub fn parse_program(s: &str) -> Result<Vec<Instr>, ParseError> {
+    s.lines()
+        .map(|line| {
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let (operands, expected) = match words.first() {
+                Some(&"inp") => (1, "expected an instruction with one operand"),
+                _ => (2, "expected an instruction with two operands"),
+            };
+
+            if words.len() != operands + 1 {
+                return Err(ParseError::at(s, line, expected));
+            }
+
+            let op = match words[0] {
+                "inp" => return Ok(Instr::Inp(register(s, words[1])?)),
+                "add" => Op::Add,
+                "mul" => Op::Mul,
+                "div" => Op::Div,
+                "mod" => Op::Mod,
+                "eql" => Op::Eql,
+                word => return Err(ParseError::at(s, word, "expected an instruction")),
+            };
+
+            let a = register(s, words[1])?;
+            let b = match register(s, words[2]) {
+                Ok(b) => Operand::Reg(b),
+                Err(_) => Operand::Num(parse_num(s, words[2])?),
+            };
+
+            Ok(Instr::Op(op, a, b))
+        })
+        .collect()
+}
+
+/// Final `[w, x, y, z]` after running `program` on `input`, or `None` if it runs out of input,
+/// overflows, divides by zero, or takes a modulo of a negative number or by a non-positive one.
+pub fn run(program: &[Instr], input: &[i64]) -> Option<[i64; 4]> {
+    let mut regs = [0; 4];
+    let mut input = input.iter();
+
+    for instr in program {
+        match *instr {
+            Instr::Inp(a) => regs[a] = *input.next()?,
+            Instr::Op(op, a, b) => {
+                let b = match b {
+                    Operand::Reg(b) => regs[b],
+                    Operand::Num(n) => n,
+                };
+
+                regs[a] = match op {
+                    Op::Add => regs[a].checked_add(b)?,
+                    Op::Mul => regs[a].checked_mul(b)?,
+                    Op::Div => regs[a].checked_div(b)?,
+                    Op::Mod if regs[a] < 0 || b <= 0 => return None,
+                    Op::Mod => regs[a] % b,
+                    Op::Eql => (regs[a] == b) as i64,
+                };
+            }
+        }
+    }
+
+    Some(regs)
+}
+
+#[test]
+fn test_run() {
+    let binary = "inp w
+add z w
+mod z 2
+div w 2
+add y w
+mod y 2
+div w 2
+add x w
+mod x 2
+div w 2
+mod w 2";
+
+    let program = parse_program(binary).unwrap();
+    assert_eq!(program[1], Instr::Op(Op::Add, Z, Operand::Reg(0)));
+    assert_eq!(program[2].to_string(), "mod z 2");
+    assert_eq!(run(&program, &[13]), Some([1, 1, 0, 1]));
+    assert_eq!(run(&program, &[]), None);
+
+    let triple = parse_program("inp z\ninp x\nmul z 3\neql z x").unwrap();
+    assert_eq!(run(&triple, &[3, 9]), Some([0, 9, 0, 1]));
+    assert_eq!(run(&triple, &[3, 8]), Some([0, 8, 0, 0]));
+
+    let negate = parse_program("inp x\nmul x -1\nmod x 2").unwrap();
+    assert_eq!(run(&negate, &[5]), None);
+    assert_eq!(run(&negate, &[-6]), Some([0, 0, 0, 0]));
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        parse_program("inp w\nadd x q1").unwrap_err().to_string(),
+        "line 2, column 7: expected a number, found `q1`"
+    );
+    assert_eq!(
+        parse_program("inp w\nsub x 1").unwrap_err().to_string(),
+        "line 2, column 1: expected an instruction, found `sub`"
+    );
+    assert_eq!(
+        parse_program("inp w 1").unwrap_err().to_string(),
+        "line 1, column 1: expected an instruction with one operand, found `inp w 1`"
+    );
+}
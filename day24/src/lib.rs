@@ -0,0 +1,67 @@
+mod alu;
+mod monad;
+
+pub use alu::*;
+pub use monad::*;
+use std::fmt::{self, Display};
+use util::{ParseError, Solution};
+
+/// A model number MONAD accepts, if there is one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Valid(u64),
+    NoValidNumber,
+}
+
+impl Outcome {
+    /// Only trusts the constraints as far as actually running MONAD agrees with them.
+    fn checked(monad: &Monad, number: Option<u64>) -> Self {
+        number
+            .filter(|&number| monad.accepts(number))
+            .map_or(Outcome::NoValidNumber, Outcome::Valid)
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Valid(number) => write!(f, "{number}"),
+            Outcome::NoValidNumber => write!(f, "no valid model number"),
+        }
+    }
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u8 = 24;
+
+    type Input = Monad;
+    type Part1 = Outcome;
+    type Part2 = Outcome;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        Monad::parse(s)
+    }
+
+    fn part1(monad: &Self::Input) -> Outcome {
+        Outcome::checked(monad, monad.largest())
+    }
+
+    fn part2(monad: &Self::Input) -> Outcome {
+        Outcome::checked(monad, monad.smallest())
+    }
+}
+
+pub fn solve(s: &str) -> Outcome {
+    Day24::solve(s)
+}
+
+pub fn bonus(s: &str) -> Outcome {
+    Day24::bonus(s)
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day24>();
+}
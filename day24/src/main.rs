@@ -0,0 +1,6 @@
+use day24::*;
+use util::*;
+
+fn main() {
+    run_day::<Day24>();
+}
@@ -0,0 +1,290 @@
+use crate::alu::{parse_program, run, Instr, Operand, Z};
+use util::ParseError;
+
+/// MONAD checks one digit per block, and the blocks only differ in the operands marked `_`.
+///
+/// Each block treats z as a stack of base 26 digits. With `div z 1` it pushes `digit + b`, as
+/// `a` is too large for the `eql` to ever match. With `div z 26` it pops a value, and pushes
+/// again unless `value + a == digit`. So for z to end at 0, every pop has to match the push
+/// it pairs up with.
+const BLOCK: [&str; 18] = [
+    "inp w", "mul x 0", "add x z", "mod x 26", "div z _", "add x _", "eql x w", "eql x 0",
+    "mul y 0", "add y 25", "mul y x", "add y 1", "mul z y", "mul y 0", "add y w", "add y _",
+    "mul y x", "add z y",
+];
+
+/// Model numbers have one digit per block.
+pub const DIGITS: usize = 14;
+
+/// `digits[later] == digits[earlier] + offset` has to hold for a model number to be valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Constraint {
+    pub earlier: usize,
+    pub later: usize,
+    pub offset: i64,
+}
+
+/// Where the program stops looking like MONAD.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub index: usize,
+    pub expected: String,
+}
+
+/// Pairs up the pushing and popping blocks of a MONAD program, which has exactly [`DIGITS`]
+/// blocks.
+pub fn analyze(program: &[Instr]) -> Result<Vec<Constraint>, Mismatch> {
+    let mut pushed = vec![];
+    let mut constraints = vec![];
+
+    for (digit, block) in program.chunks(BLOCK.len()).enumerate() {
+        let mismatch = |i: usize, expected: String| Mismatch {
+            index: digit * BLOCK.len() + i,
+            expected,
+        };
+
+        if digit == DIGITS {
+            return Err(mismatch(0, "the end of the program".into()));
+        }
+
+        let mut params = vec![];
+
+        for (i, (instr, expected)) in block.iter().zip(BLOCK).enumerate() {
+            match (expected.strip_suffix('_'), instr) {
+                (Some(prefix), Instr::Op(_, _, Operand::Num(n)))
+                    if instr.to_string().starts_with(prefix) =>
+                {
+                    params.push(*n)
+                }
+                (Some(prefix), _) => {
+                    return Err(mismatch(i, format!("`{prefix}` and a number")));
+                }
+                (None, _) if instr.to_string() == expected => {}
+                (None, _) => return Err(mismatch(i, format!("`{expected}`"))),
+            }
+        }
+
+        if block.len() < BLOCK.len() {
+            return Err(mismatch(block.len(), format!("`{}`", BLOCK[block.len()])));
+        }
+
+        let [div, a, b] = params[..] else {
+            unreachable!()
+        };
+
+        match div {
+            1 if a >= 10 => pushed.push((digit, b)),
+            1 => return Err(mismatch(5, "`add x` and a number of at least 10".into())),
+            26 if a < 10 => {
+                let Some((earlier, b)) = pushed.pop() else {
+                    return Err(mismatch(4, "`div z 1`, with nothing left to pop".into()));
+                };
+
+                constraints.push(Constraint {
+                    earlier,
+                    later: digit,
+                    offset: b + a,
+                });
+            }
+            26 => return Err(mismatch(5, "`add x` and a number below 10".into())),
+            _ => return Err(mismatch(4, "`div z 1` or `div z 26`".into())),
+        }
+    }
+
+    let blocks = program.len() / BLOCK.len();
+    if blocks < DIGITS {
+        return Err(Mismatch {
+            index: program.len(),
+            expected: format!("`inp w` for digit {}", blocks + 1),
+        });
+    }
+
+    if let Some((digit, _)) = pushed.pop() {
+        return Err(Mismatch {
+            index: program.len(),
+            expected: format!("a block popping digit {}", digit + 1),
+        });
+    }
+
+    Ok(constraints)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monad {
+    program: Vec<Instr>,
+    constraints: Vec<Constraint>,
+}
+
+impl Monad {
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let program = parse_program(s)?;
+        let constraints = analyze(&program).map_err(|Mismatch { index, expected }| {
+            let snippet = s.lines().nth(index).unwrap_or(&s[s.len()..]);
+            ParseError::at(s, snippet, format!("expected {expected}"))
+        })?;
+
+        Ok(Monad {
+            program,
+            constraints,
+        })
+    }
+
+    /// How the digits of a valid model number have to relate.
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// Checks a model number by actually running MONAD on it.
+    pub fn accepts(&self, number: u64) -> bool {
+        let digits = number
+            .to_string()
+            .bytes()
+            .map(|b| (b - b'0') as i64)
+            .collect::<Vec<_>>();
+
+        digits.len() == self.constraints.len() * 2
+            && !digits.contains(&0)
+            && run(&self.program, &digits).map_or(false, |regs| regs[Z] == 0)
+    }
+
+    /// Largest valid model number, straight from the constraints.
+    pub fn largest(&self) -> Option<u64> {
+        self.model_number(|_, hi| hi)
+    }
+
+    /// Smallest valid model number, straight from the constraints.
+    pub fn smallest(&self) -> Option<u64> {
+        self.model_number(|lo, _| lo)
+    }
+
+    /// `pick` chooses the earlier digit of every pair out of its valid range.
+    fn model_number(&self, pick: impl Fn(i64, i64) -> i64) -> Option<u64> {
+        let mut digits = vec![0; self.constraints.len() * 2];
+
+        for &Constraint {
+            earlier,
+            later,
+            offset,
+        } in &self.constraints
+        {
+            let (lo, hi) = (1.max(1 - offset), 9.min(9 - offset));
+            if lo > hi {
+                return None;
+            }
+
+            digits[earlier] = pick(lo, hi);
+            digits[later] = digits[earlier] + offset;
+        }
+
+        Some(digits.iter().fold(0, |n, &d| n * 10 + d as u64))
+    }
+}
+
+#[cfg(test)]
+fn source(params: &[(i64, i64, i64)]) -> String {
+    params
+        .iter()
+        .flat_map(|&(div, a, b)| {
+            BLOCK.iter().enumerate().map(move |(i, line)| {
+                let n = match i {
+                    4 => div,
+                    5 => a,
+                    _ => b,
+                };
+                match line.strip_suffix('_') {
+                    Some(prefix) => format!("{prefix}{n}"),
+                    None => line.to_string(),
+                }
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_model_numbers() {
+    let s = source(&[
+        (1, 12, 4),
+        (1, 11, 11),
+        (26, -5, 0),
+        (1, 14, 2),
+        (1, 10, 7),
+        (26, -11, 3),
+        (26, 0, 8),
+        (1, 13, 1),
+        (26, -3, 6),
+        (1, 15, 5),
+        (1, 12, 9),
+        (26, -14, 2),
+        (26, -8, 1),
+        (26, -6, 10),
+    ]);
+
+    let monad = Monad::parse(&s).unwrap();
+    assert_eq!(
+        monad.constraints[0],
+        Constraint {
+            earlier: 1,
+            later: 2,
+            offset: 6
+        }
+    );
+
+    assert_eq!(monad.largest(), Some(93979599799467));
+    assert_eq!(monad.smallest(), Some(31715133146111));
+    assert!(monad.accepts(93979599799467));
+    assert!(monad.accepts(31715133146111));
+    assert!(!monad.accepts(93979599799468));
+    assert!(!monad.accepts(3171513314611));
+
+    // agrees with trying every two digit number, with the other digits all paired up as equal
+    let mut params = vec![(1, 10, 3), (26, -7, 0)];
+    params.extend([(1, 10, 0), (26, 0, 0)].repeat(6));
+    let small = Monad::parse(&source(&params)).unwrap();
+
+    let rest = 111_111_111_111;
+    let valid = (11..100)
+        .filter(|&n| small.accepts(n * 1_000_000_000_000 + rest))
+        .collect::<Vec<_>>();
+    assert_eq!(valid, vec![51, 62, 73, 84, 95]);
+    assert_eq!(small.smallest(), Some(51_000_000_000_000 + rest));
+    assert_eq!(small.largest(), Some(95_999_999_999_999));
+}
+
+#[test]
+fn test_mismatch() {
+    let err = |params: &[_]| Monad::parse(&source(params)).unwrap_err().to_string();
+
+    assert_eq!(
+        err(&[(1, 10, 0); 14]),
+        "line 252, column 8: expected a block popping digit 14, found the end of the input"
+    );
+    assert_eq!(
+        err(&[(1, 10, 3), (26, -2, 0), (26, -2, 0)]),
+        "line 41, column 1: expected `div z 1`, with nothing left to pop, found `div z 26`"
+    );
+    assert_eq!(
+        err(&[(1, 3, 3), (26, -2, 0)]),
+        "line 6, column 1: expected `add x` and a number of at least 10, found `add x 3`"
+    );
+    assert_eq!(
+        Monad::parse("inp w\nmul x 0\nadd x y")
+            .unwrap_err()
+            .to_string(),
+        "line 3, column 1: expected `add x z`, found `add x y`"
+    );
+
+    // exactly one block per digit
+    assert_eq!(
+        err(&[(1, 10, 3), (26, -7, 0)]),
+        "line 36, column 8: expected `inp w` for digit 3, found the end of the input"
+    );
+    assert_eq!(
+        err(&[(1, 10, 0), (26, 0, 0)].repeat(8)[..15]),
+        "line 253, column 1: expected the end of the program, found `inp w`"
+    );
+    assert_eq!(
+        Monad::parse("").unwrap_err().to_string(),
+        "line 1, column 1: expected `inp w` for digit 1, found the end of the input"
+    );
+}
@@ -0,0 +1,179 @@
+use std::{
+    fmt::{self, Display},
+    iter,
+};
+use util::{grid::Grid, ParseError, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    East,
+    South,
+}
+
+impl Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cell::Empty => write!(f, "."),
+            Cell::East => write!(f, ">"),
+            Cell::South => write!(f, "v"),
+        }
+    }
+}
+
+/// Moves every sea cucumber of `herd` one cell in `dir` if that cell was free before any of them
+/// moved. Returns whether any did.
+fn advance(grid: &mut Grid<Cell>, herd: Cell, dir: (isize, isize)) -> bool {
+    let moving = grid
+        .iter()
+        .filter(|&(_, &cell)| cell == herd)
+        .map(|(p, _)| (p, grid.wrapping_offset(p, dir)))
+        .filter(|&(_, next)| grid[next] == Cell::Empty)
+        .collect::<Vec<_>>();
+
+    for &(p, next) in &moving {
+        grid[p] = Cell::Empty;
+        grid[next] = herd;
+    }
+
+    !moving.is_empty()
+}
+
+/// The east-facing herd moves first, then the south-facing one.
+fn step(grid: &mut Grid<Cell>) -> bool {
+    let east = advance(grid, Cell::East, (1, 0));
+    let south = advance(grid, Cell::South, (0, 1));
+    east || south
+}
+
+/// The sea floor after every step, up to the last one where something still moved. Never ends
+/// if the herds keep moving forever, see [`settle`].
+pub fn steps(grid: &Grid<Cell>) -> impl Iterator<Item = Grid<Cell>> {
+    iter::successors(Some(grid.clone()), |grid| {
+        let mut next = grid.clone();
+        step(&mut next).then_some(next)
+    })
+    .skip(1)
+}
+
+/// When the sea cucumbers stop moving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Settling {
+    /// In this step, nothing moves for the first time.
+    After(usize),
+    /// Never, as the sea floor starts repeating itself every `cycle` steps.
+    Never { cycle: usize },
+}
+
+impl Display for Settling {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Settling::After(steps) => write!(f, "{steps}"),
+            Settling::Never { cycle } => write!(f, "never, repeating every {cycle} steps"),
+        }
+    }
+}
+
+/// Detects a cycle with Brent's algorithm, so only two sea floors are kept around.
+pub fn settle(grid: &Grid<Cell>) -> Settling {
+    let mut tortoise = grid.clone();
+    let mut hare = grid.clone();
+    let (mut power, mut length) = (1, 0);
+
+    for steps in 1.. {
+        if !step(&mut hare) {
+            return Settling::After(steps);
+        }
+
+        length += 1;
+        if hare == tortoise {
+            return Settling::Never { cycle: length };
+        }
+
+        if length == power {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+    }
+
+    unreachable!()
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u8 = 25;
+
+    type Input = Grid<Cell>;
+    type Part1 = Settling;
+    type Part2 = &'static str;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        Grid::parse_with(s, |c| match c {
+            '.' => Some(Cell::Empty),
+            '>' => Some(Cell::East),
+            'v' => Some(Cell::South),
+            _ => None,
+        })
+    }
+
+    fn part1(grid: &Self::Input) -> Settling {
+        settle(grid)
+    }
+
+    /// There is no second puzzle on the last day.
+    fn part2(_: &Self::Input) -> &'static str {
+        "Merry Christmas!"
+    }
+}
+
+pub fn solve(s: &str) -> Settling {
+    Day25::solve(s)
+}
+
+#[test]
+fn test_solve() {
+    let s = "v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>";
+
+    assert_eq!(solve(s), Settling::After(58));
+    assert_eq!(solve(s).to_string(), "58");
+}
+
+#[test]
+fn test_steps() {
+    let grid = Day25::parse("...>>>>>...").unwrap();
+    let rendered = steps(&grid)
+        .take(2)
+        .map(|g| g.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(rendered, vec!["...>>>>.>..", "...>>>.>.>."]);
+
+    // spread out, they go round and round
+    let settling = Day25::part1(&grid);
+    assert_eq!(settling, Settling::Never { cycle: 11 });
+    assert_eq!(settling.to_string(), "never, repeating every 11 steps");
+
+    let grid = Day25::parse("..........\n.>v....v..\n.......>..\n..........").unwrap();
+    assert_eq!(
+        steps(&grid).next().unwrap().to_string(),
+        "..........\n.>........\n..v....v>.\n.........."
+    );
+
+    let grid = Day25::parse("..v\n>.v\nv..").unwrap();
+    assert_eq!(steps(&grid).next().unwrap().to_string(), "v.v\n.>.\n..v");
+}
+
+#[test]
+fn test_answers() {
+    util::verify::<Day25>();
+}
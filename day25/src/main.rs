@@ -0,0 +1,6 @@
+use day25::*;
+use util::*;
+
+fn main() {
+    run_day::<Day25>();
+}
@@ -0,0 +1,187 @@
+use crate::{load, workspace_root, Answers, InputError, Part, Solution};
+use std::{collections::HashMap, env, fs, path::PathBuf, str::FromStr};
+
+/// Known-good answers, read from `answers.toml`:
+///
+/// ```toml
+/// [day07]
+/// part1 = 348664
+/// part2 = "100220525"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RecordedAnswers(HashMap<(u8, Part), String>);
+
+impl RecordedAnswers {
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn check(&self, day: u8, part: Part, actual: &str) -> Verdict {
+        match self.get(day, part) {
+            None => Verdict::Unrecorded,
+            Some(expected) if expected == actual => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+
+    /// Checks whichever parts were actually run.
+    pub fn check_all(&self, day: u8, answers: &Answers) -> Vec<(Part, String, Verdict)> {
+        [(Part::One, &answers.part1), (Part::Two, &answers.part2)]
+            .into_iter()
+            .filter_map(|(part, actual)| {
+                let actual = actual.as_ref()?;
+                Some((part, actual.clone(), self.check(day, part, actual)))
+            })
+            .collect()
+    }
+}
+
+impl FromStr for RecordedAnswers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = HashMap::new();
+        let mut day = None;
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            let err = |msg: &str| format!("line {}: {msg}: `{line}`", i + 1);
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let n = section
+                    .trim()
+                    .strip_prefix("day")
+                    .and_then(|n| n.parse::<u8>().ok())
+                    .ok_or_else(|| err("expected a [dayNN] section"))?;
+                day = Some(n);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| err("expected `partN = answer`"))?;
+
+            let part = match key.trim() {
+                "part1" => Part::One,
+                "part2" => Part::Two,
+                _ => return Err(err("expected part1 or part2")),
+            };
+
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+
+            let day = day.ok_or_else(|| err("answer outside of a [dayNN] section"))?;
+            answers.insert((day, part), value.to_string());
+        }
+
+        Ok(RecordedAnswers(answers))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unrecorded,
+}
+
+/// `$AOC_ANSWERS`, or `answers.toml` in the workspace root.
+pub fn answers_path() -> PathBuf {
+    env::var_os("AOC_ANSWERS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| workspace_root().join("answers.toml"))
+}
+
+/// A missing answers file just means nothing has been recorded yet.
+pub fn load_answers() -> Result<RecordedAnswers, String> {
+    let path = answers_path();
+
+    match fs::read_to_string(&path) {
+        Ok(s) => s
+            .parse()
+            .map_err(|err| format!("{}: {err}", path.display())),
+        Err(_) if !path.exists() => Ok(RecordedAnswers::default()),
+        Err(err) => Err(format!("could not read {}: {err}", path.display())),
+    }
+}
+
+/// Test helper: runs a day on its real input and compares against the recorded answers. Days
+/// without an input are skipped rather than failed, since inputs aren't checked in.
+pub fn verify<S: Solution>() {
+    let s = match load(S::DAY, None) {
+        Ok(s) => s,
+        Err(err @ InputError::NotFound { .. }) => {
+            eprintln!("skipping day {}: {err}", S::DAY);
+            return;
+        }
+        Err(err) => panic!("{err}"),
+    };
+
+    let recorded = load_answers().unwrap();
+    let answers = crate::run::<S>(&s, None).unwrap_or_else(|err| panic!("day {}: {err}", S::DAY));
+
+    for (part, actual, verdict) in recorded.check_all(S::DAY, &answers) {
+        if let Verdict::Fail { expected } = verdict {
+            panic!("day {} {part:?}: expected {expected}, got {actual}", S::DAY);
+        }
+    }
+}
+
+#[test]
+fn test_parse() {
+    let answers = "# comment
+
+[day07]
+part1 = 348664
+part2 = \"100220525\"
+
+[day13]
+part2 = \"EFLFJGRF\"
+"
+    .parse::<RecordedAnswers>()
+    .unwrap();
+
+    assert_eq!(answers.get(7, Part::One), Some("348664"));
+    assert_eq!(answers.get(7, Part::Two), Some("100220525"));
+    assert_eq!(answers.get(13, Part::One), None);
+    assert_eq!(answers.get(13, Part::Two), Some("EFLFJGRF"));
+
+    assert!("part1 = 3".parse::<RecordedAnswers>().is_err());
+    assert!("[day7]\npart3 = 3".parse::<RecordedAnswers>().is_err());
+    assert!("[seven]".parse::<RecordedAnswers>().is_err());
+}
+
+#[test]
+fn test_check() {
+    let answers = "[day1]\npart1 = 7".parse::<RecordedAnswers>().unwrap();
+
+    assert_eq!(
+        answers.check_all(
+            1,
+            &Answers {
+                part1: Some("7".into()),
+                part2: Some("5".into())
+            }
+        ),
+        vec![
+            (Part::One, "7".into(), Verdict::Pass),
+            (Part::Two, "5".into(), Verdict::Unrecorded)
+        ]
+    );
+
+    assert_eq!(
+        answers.check(1, Part::One, "8"),
+        Verdict::Fail {
+            expected: "7".into()
+        }
+    );
+}
@@ -0,0 +1,275 @@
+use crate::{ParseError, Part, Solution};
+use std::{
+    fmt::Write,
+    hint::black_box,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchOptions {
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            warmup: 1,
+            iterations: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub iterations: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "cannot compute stats without samples");
+
+        samples.sort();
+
+        let n = samples.len();
+        let mean = samples.iter().sum::<Duration>() / n as u32;
+        let variance = samples
+            .iter()
+            .map(|d| (d.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        Self {
+            iterations: n,
+            min: samples[0],
+            median: samples[n / 2],
+            // nearest-rank
+            p95: samples[(n * 95 + 99) / 100 - 1],
+            mean,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// The statistical counterpart of [`crate::time`]: runs `f` a few times to warm up, then times each
+/// of the actual iterations.
+pub fn measure<T>(options: &BenchOptions, mut f: impl FnMut() -> T) -> Stats {
+    for _ in 0..options.warmup {
+        black_box(f());
+    }
+
+    let samples = (0..options.iterations.max(1))
+        .map(|_| {
+            let t0 = Instant::now();
+            black_box(f());
+            t0.elapsed()
+        })
+        .collect();
+
+    Stats::from_samples(samples)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchReport {
+    pub day: u8,
+    pub parse: Stats,
+    pub part1: Option<Stats>,
+    pub part2: Option<Stats>,
+}
+
+impl BenchReport {
+    pub fn stages(&self) -> impl Iterator<Item = (&'static str, &Stats)> {
+        [
+            ("parse", Some(&self.parse)),
+            ("part1", self.part1.as_ref()),
+            ("part2", self.part2.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(stage, stats)| Some((stage, stats?)))
+    }
+}
+
+/// Times parsing and each requested part separately, so it's clear where the time actually goes.
+pub fn bench<S: Solution>(
+    s: &str,
+    part: Option<Part>,
+    options: &BenchOptions,
+) -> Result<BenchReport, ParseError> {
+    let input = S::parse(s)?;
+    let parse = measure(options, || S::parse(s));
+
+    Ok(BenchReport {
+        day: S::DAY,
+        parse,
+        part1: (part != Some(Part::Two)).then(|| measure(options, || S::part1(&input))),
+        part2: (part != Some(Part::One)).then(|| measure(options, || S::part2(&input))),
+    })
+}
+
+/// Type-erased [`bench`], for dispatching over all days at once.
+pub type Bencher = fn(&str, Option<Part>, &BenchOptions) -> Result<BenchReport, ParseError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format `{s}`, expected table, json or csv")),
+        }
+    }
+}
+
+/// Table for reading, JSON and CSV (with all durations in nanoseconds) for diffing runs across
+/// commits.
+pub fn render(reports: &[BenchReport], format: Format) -> String {
+    let rows = reports.iter().flat_map(|report| {
+        report
+            .stages()
+            .map(|(stage, stats)| (report.day, stage, stats))
+    });
+
+    let mut out = String::new();
+
+    match format {
+        Format::Table => {
+            writeln!(
+                out,
+                "{:>3}  {:<5}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}",
+                "day", "stage", "n", "min", "median", "p95", "stddev"
+            )
+            .unwrap();
+
+            for (day, stage, stats) in rows {
+                writeln!(
+                    out,
+                    "{:>3}  {:<5}  {:>5}  {:>10.2?}  {:>10.2?}  {:>10.2?}  {:>10.2?}",
+                    day, stage, stats.iterations, stats.min, stats.median, stats.p95, stats.stddev
+                )
+                .unwrap();
+            }
+        }
+        Format::Csv => {
+            writeln!(
+                out,
+                "day,stage,iterations,min_ns,median_ns,p95_ns,mean_ns,stddev_ns"
+            )
+            .unwrap();
+
+            for (day, stage, stats) in rows {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{}",
+                    day,
+                    stage,
+                    stats.iterations,
+                    stats.min.as_nanos(),
+                    stats.median.as_nanos(),
+                    stats.p95.as_nanos(),
+                    stats.mean.as_nanos(),
+                    stats.stddev.as_nanos()
+                )
+                .unwrap();
+            }
+        }
+        Format::Json => {
+            let objects = rows
+                .map(|(day, stage, stats)| {
+                    format!(
+                        r#"  {{"day": {}, "stage": "{}", "iterations": {}, "min_ns": {}, "median_ns": {}, "p95_ns": {}, "mean_ns": {}, "stddev_ns": {}}}"#,
+                        day,
+                        stage,
+                        stats.iterations,
+                        stats.min.as_nanos(),
+                        stats.median.as_nanos(),
+                        stats.p95.as_nanos(),
+                        stats.mean.as_nanos(),
+                        stats.stddev.as_nanos()
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            writeln!(out, "[\n{}\n]", objects.join(",\n")).unwrap();
+        }
+    }
+
+    out
+}
+
+#[test]
+fn test_stats() {
+    let samples = (1..=20).map(Duration::from_millis).collect::<Vec<_>>();
+    let stats = Stats::from_samples(samples);
+
+    assert_eq!(stats.iterations, 20);
+    assert_eq!(stats.min, Duration::from_millis(1));
+    assert_eq!(stats.median, Duration::from_millis(11));
+    assert_eq!(stats.p95, Duration::from_millis(19));
+    assert_eq!(stats.mean, Duration::from_micros(10_500));
+    // population stddev of 1..=20 is sqrt(33.25)
+    assert_eq!(stats.stddev.as_micros(), 5766);
+
+    let stats = Stats::from_samples(vec![Duration::from_millis(7)]);
+    assert_eq!(stats.p95, Duration::from_millis(7));
+    assert_eq!(stats.stddev, Duration::ZERO);
+}
+
+#[test]
+fn test_measure() {
+    let mut calls = 0;
+    let stats = measure(
+        &BenchOptions {
+            warmup: 2,
+            iterations: 5,
+        },
+        || calls += 1,
+    );
+
+    assert_eq!(calls, 7);
+    assert_eq!(stats.iterations, 5);
+}
+
+#[test]
+fn test_render() {
+    let stats = Stats::from_samples(vec![Duration::from_nanos(100), Duration::from_nanos(300)]);
+    let reports = [BenchReport {
+        day: 3,
+        parse: stats,
+        part1: None,
+        part2: Some(stats),
+    }];
+
+    assert_eq!(
+        render(&reports, Format::Csv),
+        "day,stage,iterations,min_ns,median_ns,p95_ns,mean_ns,stddev_ns
+3,parse,2,100,300,300,200,100
+3,part2,2,100,300,300,200,100
+"
+    );
+
+    assert_eq!(
+        render(&reports, Format::Json),
+        r#"[
+  {"day": 3, "stage": "parse", "iterations": 2, "min_ns": 100, "median_ns": 300, "p95_ns": 300, "mean_ns": 200, "stddev_ns": 100},
+  {"day": 3, "stage": "part2", "iterations": 2, "min_ns": 100, "median_ns": 300, "p95_ns": 300, "mean_ns": 200, "stddev_ns": 100}
+]
+"#
+    );
+
+    assert_eq!(render(&reports, Format::Table).lines().count(), 3);
+}
@@ -0,0 +1,459 @@
+use crate::ParseError;
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+/// `(x, y)`, with `(0, 0)` the top left
+pub type Pos = (usize, usize);
+
+/// left, up, right, down
+pub const DIRS4: [(isize, isize); 4] = [(-1, 0), (0, -1), (1, 0), (0, 1)];
+
+/// row by row, skipping the center
+pub const DIRS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A rectangular grid, stored row by row in a single `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    data: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        Self {
+            width,
+            height,
+            data: (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(&mut f)
+                .collect(),
+        }
+    }
+
+    pub fn from_vec(width: usize, data: Vec<T>) -> Self {
+        assert!(
+            width > 0 && data.len() % width == 0,
+            "{} cells don't make rows of width {width}",
+            data.len()
+        );
+
+        Self {
+            width,
+            height: data.len() / width,
+            data,
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "rows should all have the same length"
+        );
+
+        Self {
+            width,
+            height: rows.len(),
+            data: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, p: Pos) -> Option<&T> {
+        self.contains(p).then(|| &self.data[p.1 * self.width + p.0])
+    }
+
+    pub fn get_mut(&mut self, p: Pos) -> Option<&mut T> {
+        if self.contains(p) {
+            Some(&mut self.data[p.1 * self.width + p.0])
+        } else {
+            None
+        }
+    }
+
+    /// Moves `p` by `(dx, dy)`, or `None` if that would leave the grid.
+    pub fn offset(&self, (x, y): Pos, (dx, dy): (isize, isize)) -> Option<Pos> {
+        let p = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(p).then_some(p)
+    }
+
+    /// Moves `p` by `(dx, dy)`, coming back in on the other side when leaving the grid.
+    pub fn wrapping_offset(&self, (x, y): Pos, (dx, dy): (isize, isize)) -> Pos {
+        (
+            (x as isize + dx).rem_euclid(self.width as isize) as usize,
+            (y as isize + dy).rem_euclid(self.height as isize) as usize,
+        )
+    }
+
+    pub fn neighbors4(&self, p: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRS4.iter().filter_map(move |&d| self.offset(p, d))
+    }
+
+    pub fn neighbors8(&self, p: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRS8.iter().filter_map(move |&d| self.offset(p, d))
+    }
+
+    /// Like [`Grid::neighbors4`], but as if the grid were a torus.
+    pub fn wrapping_neighbors4(&self, p: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRS4.iter().map(move |&d| self.wrapping_offset(p, d))
+    }
+
+    /// Like [`Grid::neighbors8`], but as if the grid were a torus.
+    pub fn wrapping_neighbors8(&self, p: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRS8.iter().map(move |&d| self.wrapping_offset(p, d))
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.data.iter()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.data.iter())
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.data.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.data.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn map<S>(&self, f: impl FnMut(&T) -> S) -> Grid<S> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            data: self.data.iter().map(f).collect(),
+        }
+    }
+
+    pub fn zip<U, S>(&self, other: &Grid<U>, mut f: impl FnMut(&T, &U) -> S) -> Grid<S> {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "can only zip grids of the same size"
+        );
+
+        Grid {
+            width: self.width,
+            height: self.height,
+            data: self
+                .data
+                .iter()
+                .zip(other.data.iter())
+                .map(|(a, b)| f(a, b))
+                .collect(),
+        }
+    }
+
+    /// Renders one character per cell, e.g. `#`/`.` for a `Grid<bool>`.
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&mut f).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Parses one cell per character, line by line.
+    pub fn parse_with(s: &str, mut f: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError> {
+        let mut lines = s.lines().collect::<Vec<_>>();
+        while lines.last().map_or(false, |line| line.is_empty()) {
+            lines.pop();
+        }
+
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        if width == 0 {
+            return Err(ParseError::at(s, &s[..0], "expected a grid"));
+        }
+
+        let mut data = Vec::with_capacity(width * lines.len());
+
+        for line in lines {
+            if line.chars().count() != width {
+                return Err(ParseError::at(
+                    s,
+                    line,
+                    format!("expected a row of {width} cells"),
+                ));
+            }
+
+            for (i, c) in line.char_indices() {
+                let cell = &line[i..i + c.len_utf8()];
+                data.push(f(c).ok_or_else(|| ParseError::at(s, cell, "invalid grid cell"))?);
+            }
+        }
+
+        Ok(Self::from_vec(width, data))
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![value; width * height],
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::new(self.height, self.width, |(x, y)| self[(y, x)].clone())
+    }
+
+    pub fn rotate_cw(&self) -> Self {
+        let h = self.height;
+        Self::new(self.height, self.width, |(x, y)| {
+            self[(y, h - 1 - x)].clone()
+        })
+    }
+
+    pub fn rotate_ccw(&self) -> Self {
+        let w = self.width;
+        Self::new(self.height, self.width, |(x, y)| {
+            self[(w - 1 - y, x)].clone()
+        })
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        let w = self.width;
+        Self::new(self.width, self.height, |(x, y)| {
+            self[(w - 1 - x, y)].clone()
+        })
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        let h = self.height;
+        Self::new(self.width, self.height, |(x, y)| {
+            self[(x, h - 1 - y)].clone()
+        })
+    }
+
+    /// Repeats the grid `nx` times horizontally and `ny` times vertically, letting `f` adjust each
+    /// copy's cells based on the tile's `(tx, ty)`.
+    pub fn tile(&self, nx: usize, ny: usize, mut f: impl FnMut(&T, (usize, usize)) -> T) -> Self {
+        Self::new(self.width * nx, self.height * ny, |(x, y)| {
+            let tile = (x / self.width, y / self.height);
+            f(&self[(x % self.width, y % self.height)], tile)
+        })
+    }
+}
+
+impl<T: From<u8>> Grid<T> {
+    pub fn from_digits(s: &str) -> Result<Self, ParseError> {
+        Self::parse_with(s, |c| Some(T::from(c.to_digit(10)? as u8)))
+    }
+}
+
+impl Grid<bool> {
+    /// `#` is true, `.` is false
+    pub fn from_hashes(s: &str) -> Result<Self, ParseError> {
+        Self::parse_with(s, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+    }
+}
+
+impl<T: From<u8>> FromStr for Grid<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_digits(s)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): Pos) -> &Self::Output {
+        assert!(
+            x < self.width && y < self.height,
+            "{:?} out of bounds",
+            (x, y)
+        );
+        &self.data[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, (x, y): Pos) -> &mut Self::Output {
+        assert!(
+            x < self.width && y < self.height,
+            "{:?} out of bounds",
+            (x, y)
+        );
+        &mut self.data[y * self.width + x]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_parse() {
+    let grid: Grid<u32> = "123\n456\n".parse().unwrap();
+
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[(0, 0)], 1);
+    assert_eq!(grid[(2, 1)], 6);
+    assert_eq!(grid.to_string(), "123\n456");
+
+    let grid = Grid::from_hashes("#.\n.#").unwrap();
+    assert_eq!(grid.values().filter(|&&b| b).count(), 2);
+    assert_eq!(grid.render(|&b| if b { '#' } else { '.' }), "#.\n.#");
+
+    assert_eq!(
+        "".parse::<Grid<u8>>().unwrap_err().to_string(),
+        "line 1, column 1: expected a grid, found the end of the input"
+    );
+    assert_eq!(
+        "12\n3".parse::<Grid<u8>>().unwrap_err().to_string(),
+        "line 2, column 1: expected a row of 2 cells, found `3`"
+    );
+    assert_eq!(
+        "12\n3x".parse::<Grid<u8>>().unwrap_err().to_string(),
+        "line 2, column 2: invalid grid cell, found `x`"
+    );
+}
+
+#[test]
+fn test_neighbors() {
+    let grid = Grid::filled(3, 3, 0);
+
+    assert_eq!(
+        grid.neighbors4((0, 0)).collect::<Vec<_>>(),
+        vec![(1, 0), (0, 1)]
+    );
+    assert_eq!(grid.neighbors4((1, 1)).count(), 4);
+    assert_eq!(
+        grid.neighbors8((2, 2)).collect::<Vec<_>>(),
+        vec![(1, 1), (2, 1), (1, 2)]
+    );
+    assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+
+    assert_eq!(grid.get((2, 2)), Some(&0));
+    assert_eq!(grid.get((3, 0)), None);
+    assert_eq!(grid.offset((0, 2), (0, 1)), None);
+    assert_eq!(grid.offset((0, 2), (1, -1)), Some((1, 1)));
+}
+
+#[test]
+fn test_wrapping_neighbors() {
+    let grid = Grid::filled(3, 2, 0);
+
+    assert_eq!(
+        grid.wrapping_neighbors4((0, 0)).collect::<Vec<_>>(),
+        vec![(2, 0), (0, 1), (1, 0), (0, 1)]
+    );
+    assert_eq!(grid.wrapping_neighbors8((2, 1)).count(), 8);
+    assert_eq!(grid.wrapping_offset((2, 1), (1, 1)), (0, 0));
+    assert_eq!(grid.wrapping_offset((0, 0), (-4, -3)), (2, 1));
+}
+
+#[test]
+fn test_rows_and_columns() {
+    let grid = Grid::new(3, 2, |(x, y)| x + 10 * y);
+
+    assert_eq!(grid.row(1), &[10, 11, 12]);
+    assert_eq!(grid.rows().count(), 2);
+    assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![2, 12]);
+    assert_eq!(grid.columns().count(), 3);
+    assert_eq!(grid.positions().last(), Some((2, 1)));
+    assert_eq!(grid.iter().nth(4), Some(((1, 1), &11)));
+}
+
+#[test]
+fn test_map_and_zip() {
+    let a = Grid::new(2, 2, |(x, y)| x + y);
+    let b = a.map(|&v| v * 10);
+
+    assert_eq!(b, Grid::from_rows(vec![vec![0, 10], vec![10, 20]]));
+    assert_eq!(
+        a.zip(&b, |x, y| x + y),
+        Grid::from_vec(2, vec![0, 11, 11, 22])
+    );
+}
+
+#[test]
+fn test_transform() {
+    // 1 2 3
+    // 4 5 6
+    let grid = Grid::from_vec(3, vec![1, 2, 3, 4, 5, 6]);
+
+    assert_eq!(grid.transpose(), Grid::from_vec(2, vec![1, 4, 2, 5, 3, 6]));
+    assert_eq!(grid.rotate_cw(), Grid::from_vec(2, vec![4, 1, 5, 2, 6, 3]));
+    assert_eq!(grid.rotate_ccw(), Grid::from_vec(2, vec![3, 6, 2, 5, 1, 4]));
+    assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+    assert_eq!(
+        grid.flip_horizontal(),
+        Grid::from_vec(3, vec![3, 2, 1, 6, 5, 4])
+    );
+    assert_eq!(
+        grid.flip_vertical(),
+        Grid::from_vec(3, vec![4, 5, 6, 1, 2, 3])
+    );
+}
+
+#[test]
+fn test_tile() {
+    let grid = Grid::from_vec(2, vec![1, 9]);
+    let tiled = grid.tile(2, 2, |&v, (tx, ty)| (v + tx + ty - 1) % 9 + 1);
+
+    assert_eq!(
+        tiled,
+        Grid::from_rows(vec![vec![1, 9, 2, 1], vec![2, 1, 3, 2]])
+    );
+}
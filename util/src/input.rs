@@ -0,0 +1,128 @@
+use std::{
+    env,
+    error::Error,
+    fmt::{self, Display},
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+pub const YEAR: u16 = 2021;
+
+/// The workspace this crate was built in, so that inputs and answers are found regardless of the
+/// working directory (`cargo test` runs in the crate's own directory).
+pub fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap()
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Read { path: PathBuf, source: io::Error },
+    Stdin(io::Error),
+    NotFound { day: u8, searched: Vec<PathBuf> },
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Read { path, source } => {
+                write!(f, "could not read {}: {source}", path.display())
+            }
+            InputError::Stdin(source) => write!(f, "could not read stdin: {source}"),
+            InputError::NotFound { day, searched } => {
+                write!(f, "no input found for day {day}, looked in:")?;
+                for path in searched {
+                    write!(f, "\n  {}", path.display())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for InputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            InputError::Read { source, .. } | InputError::Stdin(source) => Some(source),
+            InputError::NotFound { .. } => None,
+        }
+    }
+}
+
+/// Where to look for a day's input when it isn't given explicitly: first
+/// `<input_dir>/2021/dayNN.txt`, then the old `dayNN/input.txt` next to the crate.
+pub fn candidates(day: u8, input_dir: &Path) -> Vec<PathBuf> {
+    vec![
+        input_dir
+            .join(YEAR.to_string())
+            .join(format!("day{day:02}.txt")),
+        workspace_root().join(format!("day{day:02}/input.txt")),
+    ]
+}
+
+pub fn input_dir() -> PathBuf {
+    env::var_os("AOC_INPUT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| workspace_root().join("inputs"))
+}
+
+fn read(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|source| InputError::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Resolves a day's input: an explicit path (`-` meaning stdin) always wins, otherwise the first
+/// existing [`candidates`] path under `input_dir` is used.
+pub fn load_from(day: u8, explicit: Option<&str>, input_dir: &Path) -> Result<String, InputError> {
+    match explicit {
+        Some("-") => {
+            let mut s = String::new();
+            io::stdin()
+                .read_to_string(&mut s)
+                .map_err(InputError::Stdin)?;
+            Ok(s)
+        }
+        Some(path) => read(Path::new(path)),
+        None => {
+            let searched = candidates(day, input_dir);
+
+            match searched.iter().find(|path| path.is_file()) {
+                Some(path) => read(path),
+                None => Err(InputError::NotFound { day, searched }),
+            }
+        }
+    }
+}
+
+/// [`load_from`] with the input directory taken from `$AOC_INPUT_DIR`.
+pub fn load(day: u8, explicit: Option<&str>) -> Result<String, InputError> {
+    load_from(day, explicit, &input_dir())
+}
+
+#[test]
+fn test_load() {
+    let dir = env::temp_dir().join(format!("aoc-input-test-{}", std::process::id()));
+    fs::create_dir_all(dir.join("2021")).unwrap();
+    fs::write(dir.join("2021/day03.txt"), "101\n").unwrap();
+
+    assert_eq!(load_from(3, None, &dir).unwrap(), "101\n");
+
+    let explicit = dir.join("2021/day03.txt");
+    assert_eq!(
+        load_from(7, Some(explicit.to_str().unwrap()), &dir).unwrap(),
+        "101\n"
+    );
+
+    let err = load_from(99, None, &dir).unwrap_err();
+    assert!(matches!(err, InputError::NotFound { day: 99, .. }));
+    let message = err.to_string();
+    assert!(message.contains("2021/day99.txt"));
+    assert!(message.contains("day99/input.txt"));
+
+    let err = load_from(3, Some("does/not/exist.txt"), &dir).unwrap_err();
+    assert!(err.to_string().contains("does/not/exist.txt"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
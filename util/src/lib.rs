@@ -1,4 +1,20 @@
-use std::time::Instant;
+mod answers;
+mod bench;
+pub mod grid;
+mod input;
+mod memo;
+mod parse;
+pub mod search;
+mod solution;
+
+pub use answers::*;
+pub use bench::*;
+pub use input::*;
+pub use memo::*;
+pub use parse::*;
+pub use solution::*;
+
+use std::{env, process, time::Instant};
 
 pub fn time<F>(mut f: F)
 where
@@ -8,3 +24,30 @@ where
     f();
     println!("  took {:?}", t0.elapsed());
 }
+
+/// Entry point for a single day's binary, e.g. `cargo run -p day15 -- --input day15.txt`.
+pub fn run_day<S: Solution>() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let explicit = match args.as_slice() {
+        [] => None,
+        [flag, path] if flag == "--input" || flag == "-i" => Some(path.as_str()),
+        _ => {
+            eprintln!("Usage: day{:02} [--input <path|->]", S::DAY);
+            process::exit(2);
+        }
+    };
+
+    let s = load(S::DAY, explicit).unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        process::exit(1);
+    });
+
+    time(|| match run::<S>(&s, None) {
+        Ok(answers) => println!("{answers}"),
+        Err(err) => {
+            eprintln!("error: {err}");
+            process::exit(1);
+        }
+    });
+}
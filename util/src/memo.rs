@@ -0,0 +1,144 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    hash::Hash,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+};
+
+/// A cache of computed values, safe to share between threads.
+///
+/// The key has to cover everything the value depends on. Context that stays the same for all
+/// lookups (like a puzzle's rules) can be left out instead, as long as the table doesn't outlive
+/// it: keep the `Memo` next to the context, e.g. in the same struct, rather than in a global.
+#[derive(Debug)]
+pub struct Memo<K, V> {
+    table: RwLock<HashMap<K, V>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub entries: usize,
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses, {} entries",
+            self.hits, self.misses, self.entries
+        )
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            table: RwLock::new(HashMap::new()),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// The value for `key`, computing it with `f` the first time.
+    ///
+    /// The table isn't locked while `f` runs, so `f` can recurse into the same `Memo`. If two
+    /// threads miss the same key at once both compute it, and the first result is kept.
+    pub fn get_or_insert_with(&self, key: K, f: impl FnOnce() -> V) -> V {
+        if let Some(v) = self.table.read().unwrap().get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return v.clone();
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let v = f();
+
+        self.table.write().unwrap().entry(key).or_insert(v).clone()
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.table.read().unwrap().get(key).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets all values, and resets the stats.
+    pub fn clear(&self) {
+        self.table.write().unwrap().clear();
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        MemoStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.len(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn test_memo() {
+    fn fib(memo: &Memo<u64, u64>, n: u64) -> u64 {
+        if n < 2 {
+            return n;
+        }
+
+        memo.get_or_insert_with(n, || fib(memo, n - 1) + fib(memo, n - 2))
+    }
+
+    let memo = Memo::new();
+
+    assert_eq!(fib(&memo, 80), 23416728348467685);
+    assert_eq!(
+        memo.stats(),
+        MemoStats {
+            hits: 77,
+            misses: 79,
+            entries: 79
+        }
+    );
+    assert_eq!(memo.get(&10), Some(55));
+
+    memo.clear();
+    assert!(memo.is_empty());
+    assert_eq!(memo.stats(), MemoStats::default());
+}
+
+#[test]
+fn test_threads() {
+    let memo = Memo::new();
+
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for n in 0..100u64 {
+                    assert_eq!(memo.get_or_insert_with(n, || n * n), n * n);
+                }
+            });
+        }
+    });
+
+    let stats = memo.stats();
+    assert_eq!(stats.entries, 100);
+    assert_eq!(stats.hits + stats.misses, 400);
+}
@@ -0,0 +1,167 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// What went wrong while reading a puzzle input, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based, or 0 if the position is unknown
+    pub line: usize,
+    /// 1-based, counted in chars
+    pub column: usize,
+    /// the text that couldn't be parsed, empty at the end of the input
+    pub snippet: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error about `snippet`, which has to be a slice of `text` for the position to be known.
+    pub fn at(text: &str, snippet: &str, message: impl Display) -> Self {
+        let (line, column) = match offset(text, snippet) {
+            Some(offset) => {
+                let before = &text[..offset];
+                let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+                (
+                    before.matches('\n').count() + 1,
+                    before[line_start..].chars().count() + 1,
+                )
+            }
+            None => (0, 0),
+        };
+
+        Self {
+            line,
+            column,
+            snippet: snippet.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Moves an error from parsing `part` on its own (e.g. in a `FromStr` impl) to where `part`
+    /// sits in `text`.
+    pub fn within(self, text: &str, part: &str) -> Self {
+        let Some(offset) = offset(text, part) else {
+            return self;
+        };
+        let outer = ParseError::at(text, &text[offset..offset], "");
+
+        let (line, column) = match self.line {
+            0 => (outer.line, outer.column),
+            1 => (outer.line, outer.column + self.column - 1),
+            line => (outer.line + line - 1, self.column),
+        };
+
+        Self {
+            line,
+            column,
+            ..self
+        }
+    }
+}
+
+/// Byte offset of `snippet` in `text`, if it's a slice of it.
+fn offset(text: &str, snippet: &str) -> Option<usize> {
+    let start = text.as_ptr() as usize;
+    let at = snippet.as_ptr() as usize;
+
+    (start..=start + text.len())
+        .contains(&at)
+        .then_some(at - start)
+        .filter(|offset| offset + snippet.len() <= text.len())
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}, column {}: ", self.line, self.column)?;
+        }
+
+        write!(f, "{}", self.message)?;
+
+        match self.snippet.lines().next() {
+            Some(line) if line.chars().count() > 40 => {
+                write!(
+                    f,
+                    ", found `{}...`",
+                    line.chars().take(40).collect::<String>()
+                )
+            }
+            Some("") => write!(f, ", found the end of the line"),
+            Some(line) => write!(f, ", found `{line}`"),
+            None => write!(f, ", found the end of the input"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses `snippet`, a slice of `text`, as a number.
+pub fn parse_num<T: FromStr>(text: &str, snippet: &str) -> Result<T, ParseError> {
+    snippet
+        .parse()
+        .map_err(|_| ParseError::at(text, snippet, "expected a number"))
+}
+
+/// [`str::split_once`], complaining about `snippet`, a slice of `text`, if `delimiter` is missing.
+pub fn split_once<'a>(
+    text: &str,
+    snippet: &'a str,
+    delimiter: &str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    snippet
+        .split_once(delimiter)
+        .ok_or_else(|| ParseError::at(text, snippet, format!("expected `{delimiter}`")))
+}
+
+#[test]
+fn test_position() {
+    let s = "1,2\n3,x4\n";
+
+    let err = ParseError::at(s, &s[6..8], "expected a number");
+    assert_eq!((err.line, err.column), (2, 3));
+    assert_eq!(
+        err.to_string(),
+        "line 2, column 3: expected a number, found `x4`"
+    );
+
+    assert_eq!(
+        parse_num::<u32>(s, &s[6..8]),
+        Err(ParseError::at(s, &s[6..8], "expected a number"))
+    );
+    assert_eq!(parse_num::<u32>(s, &s[4..5]), Ok(3));
+    assert_eq!(split_once(s, &s[4..8], ","), Ok(("3", "x4")));
+    assert_eq!(
+        split_once(s, &s[4..8], " -> ").unwrap_err().to_string(),
+        "line 2, column 1: expected ` -> `, found `3,x4`"
+    );
+
+    let err = ParseError::at(s, &s[s.len()..], "expected another line");
+    assert_eq!(
+        err.to_string(),
+        "line 3, column 1: expected another line, found the end of the input"
+    );
+
+    let err = ParseError::at(s, &s[3..4], "expected a digit");
+    assert_eq!(
+        err.to_string(),
+        "line 1, column 4: expected a digit, found the end of the line"
+    );
+
+    let err = ParseError::at(s, "elsewhere", "oops");
+    assert_eq!(err.to_string(), "oops, found `elsewhere`");
+}
+
+#[test]
+fn test_within() {
+    let s = "a\nbb: 1,x\n";
+    let part = &s[6..9];
+
+    let err = ParseError::at(part, &part[2..], "expected a number");
+    assert_eq!((err.line, err.column), (1, 3));
+
+    let err = err.within(s, part);
+    assert_eq!((err.line, err.column), (2, 7));
+    assert_eq!(err, ParseError::at(s, &s[8..9], "expected a number"));
+}
@@ -0,0 +1,245 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Anything that can say where you can go next from a node, and what it costs to get there.
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+    /// `Default` is the zero cost
+    type Cost: Copy + Ord + Default + Add<Output = Self::Cost>;
+
+    fn neighbors(&self, node: &Self::Node) -> Vec<(Self::Node, Self::Cost)>;
+}
+
+/// Cheapest path from `start` to the first node satisfying `is_goal`, as `(cost, path)`.
+/// The path includes both ends.
+pub fn dijkstra<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    is_goal: impl FnMut(&G::Node) -> bool,
+) -> Option<(G::Cost, Vec<G::Node>)> {
+    astar(graph, start, is_goal, |_| G::Cost::default())
+}
+
+/// Like [`dijkstra`], guided by a `heuristic` that must never overestimate the remaining cost.
+pub fn astar<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    mut is_goal: impl FnMut(&G::Node) -> bool,
+    mut heuristic: impl FnMut(&G::Node) -> G::Cost,
+) -> Option<(G::Cost, Vec<G::Node>)> {
+    // every node seen so far, with the best known cost to reach it and where that came from
+    let mut nodes = vec![(start.clone(), G::Cost::default(), usize::MAX)];
+    let mut index = HashMap::from([(start.clone(), 0)]);
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), G::Cost::default(), 0))]);
+
+    while let Some(Reverse((_, cost, i))) = queue.pop() {
+        if cost > nodes[i].1 {
+            // a cheaper way here was found after this was queued
+            continue;
+        }
+
+        if is_goal(&nodes[i].0) {
+            return Some((cost, backtrack(&nodes, i, |(node, _, from)| (node, *from))));
+        }
+
+        for (next, step) in graph.neighbors(&nodes[i].0) {
+            let next_cost = cost + step;
+
+            let j = match index.entry(next) {
+                Entry::Occupied(entry) => {
+                    let j = *entry.get();
+                    if nodes[j].1 <= next_cost {
+                        continue;
+                    }
+                    nodes[j].1 = next_cost;
+                    nodes[j].2 = i;
+                    j
+                }
+                Entry::Vacant(entry) => {
+                    let j = nodes.len();
+                    nodes.push((entry.key().clone(), next_cost, i));
+                    entry.insert(j);
+                    j
+                }
+            };
+
+            let estimate = next_cost + heuristic(&nodes[j].0);
+            queue.push(Reverse((estimate, next_cost, j)));
+        }
+    }
+
+    None
+}
+
+/// Path with the fewest steps from `start` to the first node satisfying `is_goal`, as
+/// `(steps, path)`. Edge costs are ignored.
+pub fn bfs<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    is_goal: impl FnMut(&G::Node) -> bool,
+) -> Option<(usize, Vec<G::Node>)> {
+    explore(graph, start, is_goal, VecDeque::pop_front)
+}
+
+/// Some path from `start` to a node satisfying `is_goal`, not necessarily the shortest, as
+/// `(steps, path)`. Edge costs are ignored.
+pub fn dfs<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    is_goal: impl FnMut(&G::Node) -> bool,
+) -> Option<(usize, Vec<G::Node>)> {
+    explore(graph, start, is_goal, VecDeque::pop_back)
+}
+
+fn explore<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    mut is_goal: impl FnMut(&G::Node) -> bool,
+    mut next: impl FnMut(&mut VecDeque<usize>) -> Option<usize>,
+) -> Option<(usize, Vec<G::Node>)> {
+    let mut nodes = vec![(start.clone(), usize::MAX)];
+    let mut seen = HashMap::from([(start, 0)]);
+    let mut todo = VecDeque::from([0]);
+
+    while let Some(i) = next(&mut todo) {
+        if is_goal(&nodes[i].0) {
+            let path = backtrack(&nodes, i, |(node, from)| (node, *from));
+            return Some((path.len() - 1, path));
+        }
+
+        for (node, _) in graph.neighbors(&nodes[i].0) {
+            if let Entry::Vacant(entry) = seen.entry(node) {
+                nodes.push((entry.key().clone(), i));
+                entry.insert(nodes.len() - 1);
+                todo.push_back(nodes.len() - 1);
+            }
+        }
+    }
+
+    None
+}
+
+fn backtrack<N: Clone, T>(nodes: &[T], mut i: usize, link: impl Fn(&T) -> (&N, usize)) -> Vec<N> {
+    let mut path = vec![];
+
+    while i != usize::MAX {
+        let (node, from) = link(&nodes[i]);
+        path.push(node.clone());
+        i = from;
+    }
+
+    path.reverse();
+    path
+}
+
+/// Number of distinct paths from `start` to nodes satisfying `is_goal`. Paths stop at the first
+/// goal they reach, and the graph must not have cycles reachable from `start`.
+pub fn count_paths<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    mut is_goal: impl FnMut(&G::Node) -> bool,
+) -> usize {
+    fn count<G: Graph>(
+        graph: &G,
+        node: G::Node,
+        is_goal: &mut impl FnMut(&G::Node) -> bool,
+        counts: &mut HashMap<G::Node, usize>,
+    ) -> usize {
+        if is_goal(&node) {
+            return 1;
+        }
+
+        if let Some(&n) = counts.get(&node) {
+            return n;
+        }
+
+        let n = graph
+            .neighbors(&node)
+            .into_iter()
+            .map(|(next, _)| count(graph, next, is_goal, counts))
+            .sum();
+
+        counts.insert(node, n);
+        n
+    }
+
+    count(graph, start, &mut is_goal, &mut HashMap::new())
+}
+
+#[cfg(test)]
+struct Weighted(Vec<Vec<(usize, u32)>>);
+
+#[cfg(test)]
+impl Graph for Weighted {
+    type Node = usize;
+    type Cost = u32;
+
+    fn neighbors(&self, &node: &usize) -> Vec<(usize, u32)> {
+        self.0[node].clone()
+    }
+}
+
+#[test]
+fn test_shortest() {
+    //   0 --1-- 1 --1-- 2 --1-- 3
+    //    \_____________10______/      4 is unreachable
+    let graph = Weighted(vec![
+        vec![(1, 1), (3, 10)],
+        vec![(0, 1), (2, 1)],
+        vec![(1, 1), (3, 1)],
+        vec![(2, 1), (0, 10)],
+        vec![],
+    ]);
+
+    assert_eq!(
+        dijkstra(&graph, 0, |&n| n == 3),
+        Some((3, vec![0, 1, 2, 3]))
+    );
+    assert_eq!(
+        astar(&graph, 0, |&n| n == 3, |&n| 3 - n.min(3) as u32),
+        Some((3, vec![0, 1, 2, 3]))
+    );
+    assert_eq!(bfs(&graph, 0, |&n| n == 3), Some((1, vec![0, 3])));
+    assert_eq!(dijkstra(&graph, 2, |&n| n == 2), Some((0, vec![2])));
+
+    assert_eq!(dijkstra(&graph, 0, |&n| n == 4), None);
+    assert_eq!(bfs(&graph, 0, |&n| n == 4), None);
+    assert_eq!(dfs(&graph, 0, |&n| n == 4), None);
+}
+
+#[test]
+fn test_dfs() {
+    let graph = Weighted(vec![
+        vec![(1, 1), (2, 1)],
+        vec![(3, 1)],
+        vec![(3, 1)],
+        vec![],
+    ]);
+
+    let (steps, path) = dfs(&graph, 0, |&n| n == 3).unwrap();
+    assert_eq!(steps, 2);
+    assert_eq!((path[0], path[2]), (0, 3));
+}
+
+#[test]
+fn test_count_paths() {
+    // a diamond of diamonds: 2 ways through each
+    let graph = Weighted(vec![
+        vec![(1, 1), (2, 1)],
+        vec![(3, 1)],
+        vec![(3, 1)],
+        vec![(4, 1), (5, 1)],
+        vec![(6, 1)],
+        vec![(6, 1)],
+        vec![(7, 1)],
+        vec![],
+    ]);
+
+    assert_eq!(count_paths(&graph, 0, |&n| n == 6), 4);
+    assert_eq!(count_paths(&graph, 0, |&n| n == 3 || n == 6), 2);
+    assert_eq!(count_paths(&graph, 7, |&n| n == 6), 0);
+}
@@ -0,0 +1,168 @@
+use crate::{bench, Bencher, ParseError};
+use std::{
+    fmt::{self, Display},
+    time::{Duration, Instant},
+};
+
+/// A single day's puzzle: parse the input once, then answer both parts from it.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input) -> Self::Part1;
+
+    fn part2(input: &Self::Input) -> Self::Part2;
+
+    /// Part 1 straight from the input, panicking if it doesn't parse.
+    fn solve(s: &str) -> Self::Part1 {
+        Self::part1(&Self::parse(s).unwrap_or_else(|err| panic!("{err}")))
+    }
+
+    /// Part 2 straight from the input, panicking if it doesn't parse.
+    fn bonus(s: &str) -> Self::Part2 {
+        Self::part2(&Self::parse(s).unwrap_or_else(|err| panic!("{err}")))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "Part 1"),
+            Part::Two => write!(f, "Part 2"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = [(Part::One, &self.part1), (Part::Two, &self.part2)]
+            .into_iter()
+            .filter_map(|(part, answer)| Some(format!("  {part}: {}", answer.as_ref()?)))
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// How long a single [`run_timed`] spent parsing, and answering the requested parts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+/// Runs the requested part (or both, if `None`) and renders the answers, so that days with
+/// different answer types can sit in one table.
+pub fn run<S: Solution>(s: &str, part: Option<Part>) -> Result<Answers, ParseError> {
+    Ok(run_timed::<S>(s, part)?.0)
+}
+
+/// [`run`], timing the parse and the parts separately.
+pub fn run_timed<S: Solution>(
+    s: &str,
+    part: Option<Part>,
+) -> Result<(Answers, Timings), ParseError> {
+    let t0 = Instant::now();
+    let input = S::parse(s)?;
+    let parse = t0.elapsed();
+
+    let t0 = Instant::now();
+    let answers = Answers {
+        part1: (part != Some(Part::Two)).then(|| S::part1(&input).to_string()),
+        part2: (part != Some(Part::One)).then(|| S::part2(&input).to_string()),
+    };
+
+    Ok((
+        answers,
+        Timings {
+            parse,
+            solve: t0.elapsed(),
+        },
+    ))
+}
+
+/// Type-erased [`run_timed`], for dispatching over all days at once.
+pub type Runner = fn(&str, Option<Part>) -> Result<(Answers, Timings), ParseError>;
+
+/// Everything the runner needs to know about a day, without being generic over it.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub day: u8,
+    pub run: Runner,
+    pub bench: Bencher,
+}
+
+impl Day {
+    pub const fn of<S: Solution>() -> Self {
+        Self {
+            day: S::DAY,
+            run: run_timed::<S>,
+            bench: bench::<S>,
+        }
+    }
+}
+
+#[test]
+fn test_run() {
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u8 = 0;
+
+        type Input = Vec<u32>;
+        type Part1 = u32;
+        type Part2 = usize;
+
+        fn parse(s: &str) -> Result<Self::Input, ParseError> {
+            s.lines().map(|line| crate::parse_num(s, line)).collect()
+        }
+
+        fn part1(nums: &Self::Input) -> u32 {
+            nums.iter().sum()
+        }
+
+        fn part2(nums: &Self::Input) -> usize {
+            nums.len()
+        }
+    }
+
+    let s = "1\n2\n3";
+
+    assert_eq!(Sum::solve(s), 6);
+    assert_eq!(Sum::bonus(s), 3);
+    assert_eq!(
+        run::<Sum>(s, None),
+        Ok(Answers {
+            part1: Some("6".into()),
+            part2: Some("3".into())
+        })
+    );
+    assert_eq!(
+        run::<Sum>(s, Some(Part::Two)),
+        Ok(Answers {
+            part1: None,
+            part2: Some("3".into())
+        })
+    );
+    assert_eq!(
+        run::<Sum>("1\nx", None).unwrap_err().to_string(),
+        "line 2, column 1: expected a number, found `x`"
+    );
+}